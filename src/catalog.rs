@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::SaveGame;
+
+/// Human readable title for a level ID, splitting camel case and separators so
+/// `DiscoNecromancer_Remix` becomes "Disco Necromancer Remix".
+pub fn level_title(level_id: &str) -> String {
+    let mut title = String::with_capacity(level_id.len() + 4);
+    let mut previous: Option<char> = None;
+
+    for character in level_id.chars() {
+        if character == '_' || character == '-' {
+            if !title.ends_with(' ') {
+                title.push(' ');
+            }
+            previous = None;
+            continue;
+        }
+
+        let starts_word = previous.is_some_and(|previous| {
+            (previous.is_lowercase() && character.is_uppercase())
+                || (previous.is_alphabetic() && character.is_ascii_digit())
+        });
+
+        if starts_word && !title.ends_with(' ') {
+            title.push(' ');
+        }
+
+        title.push(character);
+        previous = Some(character);
+    }
+
+    title.trim().to_string()
+}
+
+/// Display name of a difficulty value.
+pub fn difficulty_name(difficulty: u64) -> String {
+    match difficulty {
+        0 => "Easy".into(),
+        1 => "Medium".into(),
+        2 => "Hard".into(),
+        3 => "Impossible".into(),
+        other => format!("Difficulty {other}"),
+    }
+}
+
+/// Letter grades from best to worst.
+pub const LETTER_GRADES: &[&str] = &["S+", "S", "A+", "A", "B+", "B", "C+", "C", "D+", "D", "F"];
+
+/// Position of a letter grade in [`LETTER_GRADES`], lower is better. Unknown
+/// and empty grades rank after every known one.
+pub fn grade_rank(grade: &str) -> usize {
+    LETTER_GRADES
+        .iter()
+        .position(|known| known.eq_ignore_ascii_case(grade.trim()))
+        .unwrap_or(LETTER_GRADES.len())
+}
+
+/// Difficulty values the game uses, from easiest to hardest.
+pub const DIFFICULTIES: &[u64] = &[0, 1, 2, 3];
+
+/// Share of the save's levels cleared on each difficulty in [`DIFFICULTIES`],
+/// from 0 to 100. Remixes aren't counted, since not every level has one.
+pub fn completion(save: &SaveGame) -> f32 {
+    let total = save.level_data.len() * DIFFICULTIES.len();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let cleared = save
+        .level_data
+        .iter()
+        .flat_map(|level| &level.difficulty_data)
+        .filter(|x| DIFFICULTIES.contains(&x.difficulty) && x.num_clears > 0)
+        .count();
+
+    cleared.min(total) as f32 / total as f32 * 100.0
+}
+
+/// Level IDs the save refers to from its storylines that don't have a level
+/// entry of their own.
+pub fn missing_level_ids(save: &SaveGame) -> Vec<String> {
+    let mut ids = save
+        .storyline_data
+        .iter()
+        .flat_map(|storyline| &storyline.story_beat_data)
+        .map(|beat| &beat.level_id)
+        .filter(|id| !id.is_empty())
+        .filter(|id| !save.level_data.iter().any(|level| &level.level_id == *id))
+        .cloned()
+        .collect::<Vec<_>>();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Fields with a known set of values, edited through the picker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PickerKind {
+    LetterGrade,
+    Difficulty,
+    StageType,
+    Language,
+    Pin,
+    LevelId,
+    EnemyId,
+}
+
+impl PickerKind {
+    /// Whether the field holds a number rather than text.
+    pub fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            PickerKind::Difficulty | PickerKind::StageType | PickerKind::EnemyId
+        )
+    }
+
+    /// Display name of a raw value of this kind.
+    pub fn name(self, value: &str) -> String {
+        match (self, value.parse::<u64>()) {
+            (_, _) if value.is_empty() => "(none)".into(),
+            (PickerKind::Difficulty, Ok(difficulty)) => difficulty_name(difficulty),
+            (PickerKind::StageType, Ok(stage_type)) => format!("Stage Type {stage_type}"),
+            (PickerKind::EnemyId, Ok(enemy_id)) => format!("Enemy {enemy_id}"),
+            (PickerKind::LevelId, _) => level_title(value),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// A value offered by the picker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PickerOption {
+    pub name: String,
+    pub value: String,
+}
+
+/// Values offered for a field. Apart from letter grades and difficulties the
+/// game's full lists aren't known, so these are the values the given saves
+/// already use. Any other value can still be typed into the picker.
+pub fn options(kind: PickerKind, saves: &[&SaveGame]) -> Vec<PickerOption> {
+    let mut values = match kind {
+        PickerKind::LetterGrade => LETTER_GRADES.iter().map(|x| x.to_string()).collect(),
+        PickerKind::Difficulty => DIFFICULTIES.iter().map(|x| x.to_string()).collect(),
+        _ => Vec::new(),
+    };
+
+    for save in saves {
+        let levels = save.level_data.iter();
+        let difficulties = levels.clone().flat_map(|level| {
+            level
+                .difficulty_data
+                .iter()
+                .chain(&level.remix_difficulty_data)
+        });
+
+        match kind {
+            PickerKind::LetterGrade => {
+                values.extend(difficulties.map(|x| x.letter_grade.clone()));
+            }
+            PickerKind::Difficulty => {
+                values.extend(difficulties.map(|x| x.difficulty.to_string()));
+            }
+            PickerKind::StageType => {
+                values.extend(levels.map(|x| x.stage_type.to_string()));
+            }
+            PickerKind::Language => values.push(save.selected_language.clone()),
+            PickerKind::Pin => {
+                values.push(save.active_cosmetic_pin.clone());
+                values.push(save.active_gameplay_pin.clone());
+            }
+            PickerKind::LevelId => {
+                values.extend(levels.map(|x| x.level_id.clone()));
+                values.extend(missing_level_ids(save));
+            }
+            PickerKind::EnemyId => values.extend(
+                save.enemy_kill_counts_by_id
+                    .iter()
+                    .map(|x| x.enemy_id.to_string()),
+            ),
+        }
+    }
+
+    let mut options = Vec::<PickerOption>::new();
+    for value in values {
+        if kind == PickerKind::LetterGrade && value.is_empty() {
+            continue;
+        }
+
+        if !options.iter().any(|x| x.value == value) {
+            options.push(PickerOption {
+                name: kind.name(&value),
+                value,
+            });
+        }
+    }
+
+    if kind.is_numeric() {
+        options.sort_by_key(|x| x.value.parse::<u64>().unwrap_or(u64::MAX));
+    } else if kind != PickerKind::LetterGrade {
+        options.sort_by(|a, b| a.value.cmp(&b.value));
+    }
+
+    options
+}
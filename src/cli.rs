@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    macros, save_file,
+    versions::{self, Version},
+    views::editor::EditorState,
+};
+
+pub const USAGE: &str =
+    "Usage: rotn-save-editor [SAVE] [--output <PATH>] [--replay <MACRO>] [--check]
+       rotn-save-editor SAVE --migrate <VERSION> [--output <PATH>]
+
+Arguments:
+  [SAVE]             Save file to open on startup
+
+Options:
+  -o, --output PATH  Write saves to PATH instead of back to SAVE
+  -r, --replay MACRO Replay the edits recorded in MACRO onto SAVE and write
+                     the result without opening the editor
+  -c, --check        Check that SAVE parses, showing where it doesn't
+  -m, --migrate VERSION
+                     Upgrade or downgrade SAVE to VERSION, written as
+                     GAME/SAVE like the save's GameDataVersion and
+                     SaveDataVersion
+  -h, --help         Print this help";
+
+#[derive(Clone, Debug, Default)]
+pub struct Args {
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub check: bool,
+    pub migrate: Option<Version>,
+    pub help: bool,
+}
+
+impl Args {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "-c" | "--check" => parsed.check = true,
+                "-o" | "--output" => {
+                    let Some(path) = args.next() else {
+                        return Err(format!("Missing value for {arg}"));
+                    };
+                    parsed.output = Some(PathBuf::from(path));
+                }
+                _ if arg.starts_with("--output=") => {
+                    parsed.output = Some(PathBuf::from(&arg["--output=".len()..]));
+                }
+                "-r" | "--replay" => {
+                    let Some(path) = args.next() else {
+                        return Err(format!("Missing value for {arg}"));
+                    };
+                    parsed.replay = Some(PathBuf::from(path));
+                }
+                _ if arg.starts_with("--replay=") => {
+                    parsed.replay = Some(PathBuf::from(&arg["--replay=".len()..]));
+                }
+                "-m" | "--migrate" => {
+                    let Some(version) = args.next() else {
+                        return Err(format!("Missing value for {arg}"));
+                    };
+                    parsed.migrate = Some(version.parse().map_err(|err| format!("{err}"))?);
+                }
+                _ if arg.starts_with("--migrate=") => {
+                    let version = &arg["--migrate=".len()..];
+                    parsed.migrate = Some(version.parse().map_err(|err| format!("{err}"))?);
+                }
+                _ if arg.starts_with('-') => return Err(format!("Unknown option {arg}")),
+                _ if parsed.input.is_none() => parsed.input = Some(PathBuf::from(arg)),
+                _ => return Err(format!("Unexpected argument {arg}")),
+            }
+        }
+
+        if parsed.output.is_some() && parsed.input.is_none() {
+            return Err("--output needs a SAVE to write elsewhere".to_string());
+        }
+        if parsed.replay.is_some() && parsed.input.is_none() {
+            return Err("--replay needs a SAVE to replay onto".to_string());
+        }
+        if parsed.check && parsed.replay.is_some() {
+            return Err("--check only loads SAVE and can't be combined with --replay".to_string());
+        }
+        if parsed.check && parsed.input.is_none() {
+            return Err("--check needs a SAVE to check".to_string());
+        }
+        if parsed.migrate.is_some() && parsed.input.is_none() {
+            return Err("--migrate needs a SAVE to migrate".to_string());
+        }
+        if parsed.migrate.is_some() && (parsed.check || parsed.replay.is_some()) {
+            return Err("--migrate can't be combined with --check or --replay".to_string());
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Loads `input` the same way the editor does, to see whether it parses.
+pub fn check(input: &Path) -> Result<(), String> {
+    let (save, _) = save_file::load(input)
+        .map_err(|err| format!("Failed to load {}: {}", input.display(), err))?;
+    println!("{} parses", input.display());
+
+    let version = Version {
+        game_data: save.game_data_version,
+        save_data: save.save_data_version,
+    };
+    if !version.is_known() {
+        eprintln!("Warning: version {version} isn't one the editor knows");
+    }
+
+    Ok(())
+}
+
+/// Migrates `input` to `target` and writes it to `output`. Works on saves
+/// that don't parse as `SaveGame`, which older versions may not.
+pub fn migrate(input: &Path, target: Version, output: &Path) -> Result<(), String> {
+    let (mut save, encoding) = save_file::load_value(input)
+        .map_err(|err| format!("Failed to load {}: {}", input.display(), err))?;
+
+    let steps = versions::migrate(&mut save, target).map_err(|err| err.to_string())?;
+    println!("Migrated to version {target} in {steps} steps");
+
+    save_file::write(output, &save, encoding)
+        .map_err(|err| format!("Failed to save {}: {}", output.display(), err))
+}
+
+/// Replays the macro at `path` onto `input` and writes the result to
+/// `output`, printing the steps that were skipped.
+pub fn replay(input: &Path, path: &Path, output: &Path) -> Result<(), String> {
+    let (save, encoding) = save_file::load(input)
+        .map_err(|err| format!("Failed to load {}: {}", input.display(), err))?;
+    let recorded =
+        macros::load(path).map_err(|err| format!("Failed to load {}: {}", path.display(), err))?;
+
+    let mut state = EditorState::new(save, output.to_path_buf(), encoding);
+    let skipped = state.replay(&recorded);
+
+    for step in &skipped {
+        eprintln!("Skipped {step}");
+    }
+    println!(
+        "Replayed {} of {} steps",
+        recorded.steps.len() - skipped.len(),
+        recorded.steps.len()
+    );
+
+    save_file::write(output, state.data(), encoding)
+        .map_err(|err| format!("Failed to save {}: {}", output.display(), err))
+}
@@ -0,0 +1,108 @@
+//! Settings kept between launches, stored as JSON in the user's config
+//! folder.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::views::editor::EditorSession;
+
+/// Number of recently opened saves remembered.
+const RECENT_LIMIT: usize = 10;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Recently opened saves, the latest first.
+    pub recent: Vec<PathBuf>,
+    /// Whether the saves open when the app was closed are opened again on
+    /// start.
+    pub reopen_last: bool,
+    /// One per tab that had a save open when the app was last closed.
+    pub sessions: Vec<Session>,
+}
+
+/// A save open when the app was last closed, and how it was being looked
+/// at.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Session {
+    pub path: PathBuf,
+    pub editor: EditorSession,
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("There is no config folder on this system")]
+    NoConfigDir,
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// Folder the editor keeps its own files in.
+pub fn dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|x| x.join("rotn-save-editor"))
+}
+
+fn path() -> Option<PathBuf> {
+    dir().map(|x| x.join("config.json"))
+}
+
+impl Config {
+    /// The stored config, or the default one if there is none or it can't
+    /// be read.
+    pub fn load() -> Self {
+        let Some(path) = path() else {
+            return Self::default();
+        };
+
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(err) => {
+                tracing::warn!("Failed to read {}: {}", path.display(), err);
+                return Self::default();
+            }
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|err| {
+            tracing::warn!("Failed to parse {}: {}", path.display(), err);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let path = path().ok_or(ConfigError::NoConfigDir)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Moves `path` to the top of the recent saves.
+    pub fn add_recent(&mut self, path: &Path) {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+
+        self.recent.retain(|x| *x != path);
+        self.recent.insert(0, path);
+        self.recent.truncate(RECENT_LIMIT);
+    }
+
+    /// The sessions to restore on start, leaving out saves that no longer
+    /// exist.
+    pub fn last_sessions(&self) -> Vec<Session> {
+        if !self.reopen_last {
+            return Vec::new();
+        }
+
+        self.sessions
+            .iter()
+            .filter(|session| session.path.is_file())
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,314 @@
+//! Explains why a save failed to parse: which field, what was expected there
+//! and what the file looks like around it.
+
+use std::fmt::Display;
+
+use serde_json::Value;
+
+use crate::{
+    diff::{Path, Segment},
+    schema::{self, Issue},
+};
+
+/// Characters of a line shown on each side of the error. Saves are usually
+/// written on a single line, so the excerpt is a window around the error.
+const EXCERPT_RADIUS: usize = 40;
+/// Lines shown before and after the one with the error.
+const EXCERPT_CONTEXT: usize = 2;
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    /// `serde_json`'s message, without the position.
+    pub message: String,
+    /// Field the error is in, as far as it can be told.
+    pub path: Path,
+    /// Expected and actual type, when the file is valid JSON that doesn't fit
+    /// the save format.
+    pub issue: Option<Issue>,
+    pub line: usize,
+    pub column: usize,
+    pub excerpt: Vec<ExcerptLine>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExcerptLine {
+    pub number: usize,
+    pub text: String,
+    /// Position of the error in `text`, on the line that has it.
+    pub caret: Option<usize>,
+}
+
+impl Diagnostic {
+    pub fn new(source: &str, err: &serde_json::Error) -> Self {
+        let (line, column) = (err.line(), err.column());
+        let location = format!(" at line {line} column {column}");
+        let message = err.to_string();
+        let message = message
+            .strip_suffix(&location)
+            .unwrap_or(&message)
+            .to_string();
+
+        let mut path = path_at(source, offset(source, line, column));
+
+        // serde stops at the first field that doesn't fit and only says
+        // what it wanted, so the field is looked up again in the parsed
+        // document to get both types.
+        let issue = err
+            .is_data()
+            .then(|| serde_json::from_str::<Value>(source).ok())
+            .flatten()
+            .and_then(|value| {
+                let issues = schema::check(&value);
+                issues
+                    .iter()
+                    .find(|issue| issue.path.0.starts_with(&path.0))
+                    .or(issues.first())
+                    .cloned()
+            });
+        if let Some(issue) = &issue {
+            path = issue.path.clone();
+        }
+
+        Self {
+            message,
+            path,
+            issue,
+            line,
+            column,
+            excerpt: excerpt(source, line, column),
+        }
+    }
+
+    /// One line saying where and what the error is.
+    pub fn summary(&self) -> String {
+        match &self.issue {
+            Some(issue) => issue.to_string(),
+            None if self.path.0.is_empty() => self.message.clone(),
+            None => format!("{}: {}", self.path, self.message),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.summary())?;
+        write!(f, "  --> line {}, column {}", self.line, self.column)?;
+
+        let width = self
+            .excerpt
+            .iter()
+            .map(|x| x.number.to_string().len())
+            .max()
+            .unwrap_or(0);
+
+        for line in &self.excerpt {
+            write!(f, "\n{:>width$} | {}", line.number, line.text)?;
+            if let Some(caret) = line.caret {
+                write!(f, "\n{:>width$} | {}^", "", " ".repeat(caret))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Byte offset of a 1-based line and column as `serde_json` reports them.
+fn offset(source: &str, line: usize, column: usize) -> usize {
+    let start = source
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+
+    let mut offset = (start + column).min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// Path of the value being read at byte `offset` of `source`, as far as the
+/// JSON up to there tells. Works on broken files too, it only looks at
+/// brackets, keys and commas.
+pub fn path_at(source: &str, offset: usize) -> Path {
+    enum Frame {
+        Object { key: Option<String> },
+        Array { index: usize },
+    }
+
+    let mut stack = Vec::new();
+    let mut chars = source[..offset].chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '"' => {
+                let mut string = String::new();
+                let mut escaped = false;
+                for char in chars.by_ref() {
+                    match char {
+                        '"' if !escaped => break,
+                        '\\' if !escaped => escaped = true,
+                        char => {
+                            string.push(char);
+                            escaped = false;
+                        }
+                    }
+                }
+
+                if let Some(Frame::Object { key: key @ None }) = stack.last_mut() {
+                    *key = Some(string);
+                }
+            }
+            '{' => stack.push(Frame::Object { key: None }),
+            '[' => stack.push(Frame::Array { index: 0 }),
+            '}' | ']' => {
+                stack.pop();
+            }
+            ',' => match stack.last_mut() {
+                Some(Frame::Object { key }) => *key = None,
+                Some(Frame::Array { index }) => *index += 1,
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    let mut path = Path::default();
+    for frame in stack {
+        match frame {
+            Frame::Object { key: Some(key) } => path.0.push(Segment::Field(key)),
+            Frame::Object { key: None } => break,
+            Frame::Array { index } => path.0.push(Segment::Index(index)),
+        }
+    }
+    path
+}
+
+/// The lines around the error, cut down to a window around its column.
+fn excerpt(source: &str, line: usize, column: usize) -> Vec<ExcerptLine> {
+    let lines = source.lines().collect::<Vec<_>>();
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    let index = line.clamp(1, lines.len()) - 1;
+    let error_line = lines[index];
+    let column = error_line
+        .char_indices()
+        .take_while(|(position, _)| *position < column)
+        .count();
+    let start = column.saturating_sub(EXCERPT_RADIUS);
+    let end = column + EXCERPT_RADIUS;
+
+    let first = index.saturating_sub(EXCERPT_CONTEXT);
+    let last = (index + EXCERPT_CONTEXT).min(lines.len() - 1);
+
+    (first..=last)
+        .map(|number| {
+            let full = lines[number];
+            let length = full.chars().count();
+            let prefix = if start > 0 && length > start {
+                "..."
+            } else {
+                ""
+            };
+            let suffix = if length > end { "..." } else { "" };
+
+            let text = format!(
+                "{prefix}{}{suffix}",
+                full.chars()
+                    .skip(start)
+                    .take(end - start)
+                    .collect::<String>()
+            );
+            let caret = (number == index)
+                .then(|| prefix.len() + column.saturating_sub(start).saturating_sub(1));
+
+            ExcerptLine {
+                number: number + 1,
+                text,
+                caret,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_path_of_nested_values() {
+        let source = r#"{"SaveName":"a, [b]","LevelDatas":[{},{"LevelId":"x"},{"DifficultyHighScoreDatas":[]},{"LevelId":"y","DifficultyHighScoreDatas":[{"NumClears":1},{"Difficulty":2,"NumClears":"#;
+        let path = path_at(source, source.len());
+
+        assert_eq!(
+            path.to_string(),
+            "LevelDatas[3].DifficultyHighScoreDatas[1].NumClears"
+        );
+    }
+
+    #[test]
+    fn stops_at_objects_between_fields() {
+        let source = r#"{"LevelDatas":[{"LevelId":"x"},"#;
+
+        assert_eq!(path_at(source, source.len()).to_string(), "LevelDatas[1]");
+        assert_eq!(path_at(r#"{"A":1,"#, 7).to_string(), "");
+    }
+
+    #[test]
+    fn points_at_the_error_column() {
+        let source = "one\ntwo\nthree\nfour\nfive\nsix";
+        let excerpt = excerpt(source, 3, 4);
+
+        let numbers = excerpt.iter().map(|x| x.number).collect::<Vec<_>>();
+        assert_eq!(numbers, [1, 2, 3, 4, 5]);
+        assert_eq!(excerpt[2].text, "three");
+        assert_eq!(excerpt[2].caret, Some(3));
+        assert!(excerpt.iter().filter(|x| x.caret.is_some()).count() == 1);
+    }
+
+    #[test]
+    fn shows_context_after_the_first_line() {
+        let source = "one\ntwo\nthree\nfour";
+        let excerpt = excerpt(source, 1, 1);
+
+        let numbers = excerpt.iter().map(|x| x.number).collect::<Vec<_>>();
+        assert_eq!(numbers, [1, 2, 3]);
+        assert_eq!(excerpt[0].caret, Some(0));
+    }
+
+    #[test]
+    fn shows_context_before_the_last_line() {
+        let source = "one\ntwo\nthree\nfour";
+        let excerpt = excerpt(source, 4, 4);
+
+        let numbers = excerpt.iter().map(|x| x.number).collect::<Vec<_>>();
+        assert_eq!(numbers, [2, 3, 4]);
+        assert_eq!(excerpt[2].text, "four");
+        assert_eq!(excerpt[2].caret, Some(3));
+    }
+
+    #[test]
+    fn cuts_long_lines_around_the_error() {
+        let source = format!("{}x{}", "a".repeat(100), "b".repeat(100));
+        let excerpt = excerpt(&source, 1, 101);
+
+        let line = &excerpt[0];
+        assert!(line.text.starts_with("...") && line.text.ends_with("..."));
+        assert_eq!(line.text[line.caret.unwrap()..].chars().next(), Some('x'));
+    }
+
+    #[test]
+    fn reports_where_a_save_doesnt_parse() {
+        let source = "{\n  \"SaveName\": \"a\",\n  \"TimesBooted\": \"often\"\n}";
+        let err = serde_json::from_str::<crate::models::SaveGame>(source).unwrap_err();
+        let diagnostic = Diagnostic::new(source, &err);
+
+        assert_eq!(diagnostic.path.to_string(), "TimesBooted");
+        assert_eq!(diagnostic.line, 3);
+        let line = &diagnostic.excerpt[2];
+        assert_eq!(line.number, 3);
+        assert!(line.caret.is_some());
+    }
+}
@@ -0,0 +1,486 @@
+//! Structural diff between two saves.
+//!
+//! Lists are matched by a key field where the save has one, so reordering,
+//! inserting or removing entries only reports the entries that actually
+//! changed instead of everything after them.
+
+use std::{borrow::Cow, fmt::Display};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::models::{DifficultyData, EnemyKillCount, LevelData, StorylineData};
+
+/// Field identifying each entry of the list stored under the given field.
+pub fn list_key(field: &str) -> Option<&'static str> {
+    match field {
+        "LevelDatas" => Some("LevelId"),
+        "DifficultyHighScoreDatas" | "RemixDifficultyHighScoreDatas" => Some("Difficulty"),
+        "EnemyKillCountsById" => Some("EnemyId"),
+        "StorylineDatas" => Some("storylineCharacters"),
+        "StoryBeatDatas" => Some("LevelId"),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Segment {
+    Field(String),
+    Index(usize),
+    /// List entry whose `field` equals `value`.
+    Key {
+        field: Cow<'static, str>,
+        value: Value,
+    },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Path(pub Vec<Segment>);
+
+impl Path {
+    pub fn push(&self, segment: Segment) -> Path {
+        let mut path = self.clone();
+        path.0.push(segment);
+        path
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (position, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Field(name) if position == 0 => write!(f, "{name}")?,
+                Segment::Field(name) => write!(f, ".{name}")?,
+                Segment::Index(index) => write!(f, "[{index}]")?,
+                Segment::Key {
+                    value: Value::String(value),
+                    ..
+                } => write!(f, "[{value}]")?,
+                Segment::Key { field, value } => write!(f, "[{field}={value}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single difference. `old` is missing for added entries and `new` for
+/// removed ones.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Change {
+    pub path: Path,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+    /// Where an entry added to or removed from a keyed list sits in the list
+    /// that has it, so reverting puts it back in the same place.
+    #[serde(default)]
+    pub position: Option<usize>,
+}
+
+impl Change {
+    /// The change going the other way.
+    pub fn inverse(&self) -> Change {
+        Change {
+            path: self.path.clone(),
+            old: self.new.clone(),
+            new: self.old.clone(),
+            position: self.position,
+        }
+    }
+}
+
+pub fn diff<T: Serialize>(original: &T, current: &T) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    if let (Ok(original), Ok(current)) = (
+        serde_json::to_value(original),
+        serde_json::to_value(current),
+    ) {
+        diff_values(&Path::default(), None, &original, &current, &mut changes);
+    }
+
+    changes
+}
+
+fn diff_values(
+    path: &Path,
+    field: Option<&str>,
+    old: &Value,
+    new: &Value,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (name, old_value) in old {
+                let path = path.push(Segment::Field(name.clone()));
+                match new.get(name) {
+                    Some(new_value) => {
+                        diff_values(&path, Some(name), old_value, new_value, changes)
+                    }
+                    None => changes.push(Change {
+                        path,
+                        old: Some(old_value.clone()),
+                        new: None,
+                        position: None,
+                    }),
+                }
+            }
+
+            for (name, new_value) in new.iter().filter(|(name, _)| !old.contains_key(*name)) {
+                changes.push(Change {
+                    path: path.push(Segment::Field(name.clone())),
+                    old: None,
+                    new: Some(new_value.clone()),
+                    position: None,
+                });
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => match field.and_then(list_key) {
+            Some(key) => diff_keyed(path, key, old, new, changes),
+            None => diff_indexed(path, old, new, changes),
+        },
+        (old, new) if old != new => changes.push(Change {
+            path: path.clone(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+            position: None,
+        }),
+        _ => {}
+    }
+}
+
+fn diff_indexed(path: &Path, old: &[Value], new: &[Value], changes: &mut Vec<Change>) {
+    for index in 0..old.len().max(new.len()) {
+        let path = path.push(Segment::Index(index));
+        match (old.get(index), new.get(index)) {
+            (Some(old), Some(new)) => diff_values(&path, None, old, new, changes),
+            (old, new) => changes.push(Change {
+                path,
+                old: old.cloned(),
+                new: new.cloned(),
+                position: None,
+            }),
+        }
+    }
+}
+
+fn diff_keyed(
+    path: &Path,
+    key: &'static str,
+    old: &[Value],
+    new: &[Value],
+    changes: &mut Vec<Change>,
+) {
+    let mut matched = vec![false; old.len()];
+    // Added entries are listed after the removed ones, so applying the
+    // changes in order puts each at its index in the new list.
+    let mut added = Vec::new();
+
+    for (index, new_value) in new.iter().enumerate() {
+        let Some(value) = new_value.get(key) else {
+            // Entries without the key can only be compared by position, with
+            // an entry that doesn't have it either and isn't matched yet.
+            let path = path.push(Segment::Index(index));
+            let old_value = old
+                .get(index)
+                .filter(|x| !matched[index] && x.get(key).is_none());
+            match old_value {
+                Some(old_value) => {
+                    matched[index] = true;
+                    diff_values(&path, None, old_value, new_value, changes)
+                }
+                None => added.push(Change {
+                    path,
+                    old: None,
+                    new: Some(new_value.clone()),
+                    position: None,
+                }),
+            }
+            continue;
+        };
+
+        let path = path.push(Segment::Key {
+            field: Cow::Borrowed(key),
+            value: value.clone(),
+        });
+
+        let position = (0..old.len())
+            .find(|position| !matched[*position] && old[*position].get(key) == Some(value));
+
+        match position {
+            Some(position) => {
+                matched[position] = true;
+                diff_values(&path, None, &old[position], new_value, changes);
+            }
+            None => added.push(Change {
+                path,
+                old: None,
+                new: Some(new_value.clone()),
+                position: Some(index),
+            }),
+        }
+    }
+
+    // Last first, so removing an entry by index doesn't move the others.
+    for (position, old_value) in old.iter().enumerate().rev().filter(|(x, _)| !matched[*x]) {
+        let (segment, position) = match old_value.get(key) {
+            Some(value) => (
+                Segment::Key {
+                    field: Cow::Borrowed(key),
+                    value: value.clone(),
+                },
+                Some(position),
+            ),
+            None => (Segment::Index(position), None),
+        };
+
+        changes.push(Change {
+            path: path.push(segment),
+            old: Some(old_value.clone()),
+            new: None,
+            position,
+        });
+    }
+
+    changes.append(&mut added);
+}
+
+/// The entry of `original` with the same `LevelId` as `level`.
+pub fn match_level<'a>(original: &'a [LevelData], level: &LevelData) -> Option<&'a LevelData> {
+    original.iter().find(|x| x.level_id == level.level_id)
+}
+
+/// The entry of `original` for the same difficulty as `data`.
+pub fn match_difficulty<'a>(
+    original: &'a [DifficultyData],
+    data: &DifficultyData,
+) -> Option<&'a DifficultyData> {
+    original.iter().find(|x| x.difficulty == data.difficulty)
+}
+
+/// The entry of `original` for the same enemy as `enemy`.
+pub fn match_enemy<'a>(
+    original: &'a [EnemyKillCount],
+    enemy: &EnemyKillCount,
+) -> Option<&'a EnemyKillCount> {
+    original.iter().find(|x| x.enemy_id == enemy.enemy_id)
+}
+
+/// The entry of `original` for the same storyline as `storyline`.
+pub fn match_storyline<'a>(
+    original: &'a [StorylineData],
+    storyline: &StorylineData,
+) -> Option<&'a StorylineData> {
+    original
+        .iter()
+        .find(|x| x.storyline_characters == storyline.storyline_characters)
+}
+
+/// `current` with the single `change` undone, or `None` if the change no
+/// longer applies to it.
+pub fn revert<T: Serialize + DeserializeOwned>(current: &T, change: &Change) -> Option<T> {
+    let mut value = serde_json::to_value(current).ok()?;
+
+    let (last, parents) = change.path.0.split_last()?;
+    let mut parent = &mut value;
+    for segment in parents {
+        parent = match segment {
+            Segment::Field(name) => parent.get_mut(name)?,
+            Segment::Index(index) => parent.get_mut(*index)?,
+            Segment::Key { field, value } => parent
+                .as_array_mut()?
+                .iter_mut()
+                .find(|x| x.get(field.as_ref()) == Some(value))?,
+        };
+    }
+
+    match (last, change.old.clone()) {
+        (Segment::Field(name), Some(old)) => {
+            parent.as_object_mut()?.insert(name.clone(), old);
+        }
+        (Segment::Field(name), None) => {
+            parent.as_object_mut()?.remove(name);
+        }
+        (Segment::Index(index), old) => {
+            let list = parent.as_array_mut()?;
+            match (old, change.new.is_some()) {
+                (Some(old), true) => *list.get_mut(*index)? = old,
+                (Some(old), false) => list.insert((*index).min(list.len()), old),
+                (None, _) if *index < list.len() => {
+                    list.remove(*index);
+                }
+                (None, _) => return None,
+            }
+        }
+        (Segment::Key { field, value }, old) => {
+            let list = parent.as_array_mut()?;
+            let position = list
+                .iter()
+                .position(|x| x.get(field.as_ref()) == Some(value));
+            match (position, old) {
+                (Some(position), Some(old)) => list[position] = old,
+                (Some(position), None) => {
+                    list.remove(position);
+                }
+                (None, Some(old)) => {
+                    let position = change.position.unwrap_or(list.len()).min(list.len());
+                    list.insert(position, old);
+                }
+                (None, None) => return None,
+            }
+        }
+    }
+
+    serde_json::from_value(value).ok()
+}
+
+/// `current` with `changes` made again, such as ones taken from a diff
+/// against an earlier version of it. Changes that no longer apply are left
+/// out, their number is returned alongside.
+pub fn reapply<T: Clone + Serialize + DeserializeOwned>(
+    current: &T,
+    changes: &[Change],
+) -> (T, usize) {
+    let mut result = current.clone();
+    let mut skipped = 0;
+
+    for change in changes {
+        match revert(&result, &change.inverse()) {
+            Some(next) => result = next,
+            None => skipped += 1,
+        }
+    }
+
+    (result, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::templates;
+
+    fn key(field: &'static str, value: Value) -> Segment {
+        Segment::Key {
+            field: Cow::Borrowed(field),
+            value,
+        }
+    }
+
+    #[test]
+    fn keyed_lists_only_report_changed_entries() {
+        let old = json!({ "LevelDatas": [
+            { "LevelId": "A", "StageType": 0 },
+            { "LevelId": "B", "StageType": 0 },
+        ]});
+        let new = json!({ "LevelDatas": [
+            { "LevelId": "B", "StageType": 1 },
+            { "LevelId": "A", "StageType": 0 },
+        ]});
+
+        let changes = diff(&old, &new);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].path,
+            Path(vec![
+                Segment::Field("LevelDatas".into()),
+                key("LevelId", json!("B")),
+                Segment::Field("StageType".into()),
+            ])
+        );
+        assert_eq!(changes[0].old, Some(json!(0)));
+        assert_eq!(changes[0].new, Some(json!(1)));
+    }
+
+    #[test]
+    fn keyed_lists_report_added_and_removed_entries_with_their_position() {
+        let old = json!({ "LevelDatas": [{ "LevelId": "A" }, { "LevelId": "B" }] });
+        let new = json!({ "LevelDatas": [{ "LevelId": "C" }, { "LevelId": "B" }] });
+
+        let changes = diff(&old, &new);
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].old, Some(json!({ "LevelId": "A" })));
+        assert_eq!(changes[0].new, None);
+        assert_eq!(changes[0].position, Some(0));
+        assert_eq!(changes[1].old, None);
+        assert_eq!(changes[1].new, Some(json!({ "LevelId": "C" })));
+        assert_eq!(changes[1].position, Some(0));
+    }
+
+    #[test]
+    fn entries_without_the_key_dont_match_keyed_ones() {
+        let old = json!({ "LevelDatas": [{ "LevelId": "A" }] });
+        let new = json!({ "LevelDatas": [{ "Other": 1 }, { "LevelId": "A" }] });
+
+        let changes = diff(&old, &new);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].old, None);
+        assert_eq!(changes[0].new, Some(json!({ "Other": 1 })));
+    }
+
+    #[test]
+    fn revert_puts_removed_entries_back_in_place() {
+        let mut original = templates::save();
+        for id in ["A", "B", "C"] {
+            original.level_data.push(templates::level(id.into()));
+        }
+        let mut edited = original.clone();
+        edited.level_data.remove(1);
+
+        let changes = diff(&original, &edited);
+        assert_eq!(changes.len(), 1);
+
+        let reverted = revert(&edited, &changes[0]).unwrap();
+        assert_eq!(reverted, original);
+    }
+
+    #[test]
+    fn revert_fails_when_the_change_no_longer_applies() {
+        let original = json!({ "LevelDatas": [{ "LevelId": "A", "StageType": 0 }] });
+        let edited = json!({ "LevelDatas": [{ "LevelId": "A", "StageType": 1 }] });
+        let changes = diff(&original, &edited);
+
+        let unrelated = json!({ "LevelDatas": [] });
+        assert_eq!(revert(&unrelated, &changes[0]), None);
+    }
+
+    #[test]
+    fn reapply_makes_the_changes_again() {
+        let mut original = templates::save();
+        for id in ["A", "B", "C"] {
+            original.level_data.push(templates::level(id.into()));
+        }
+        original.enemy_kill_counts_by_id.push(templates::enemy(3));
+
+        let mut edited = original.clone();
+        edited.save_name = "Edited".into();
+        edited.level_data.remove(0);
+        edited.level_data.insert(1, templates::level("D".into()));
+        edited.level_data[0].awarded_diamonds = 5;
+        edited.enemy_kill_counts_by_id.clear();
+
+        let changes = diff(&original, &edited);
+        let (reapplied, skipped) = reapply(&original, &changes);
+
+        assert_eq!(skipped, 0);
+        assert_eq!(reapplied, edited);
+    }
+
+    #[test]
+    fn reapply_skips_changes_that_no_longer_apply() {
+        let mut original = templates::save();
+        original.level_data.push(templates::level("A".into()));
+        let mut edited = original.clone();
+        edited.level_data[0].awarded_diamonds = 5;
+        edited.times_booted = 3;
+
+        let changes = diff(&original, &edited);
+        let (reapplied, skipped) = reapply(&templates::save(), &changes);
+
+        assert_eq!(skipped, 1);
+        assert_eq!(reapplied.times_booted, 3);
+    }
+}
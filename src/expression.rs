@@ -0,0 +1,273 @@
+//! Arithmetic used by the numeric field editor.
+//!
+//! Supports `+ - * /`, parentheses and the names `value`, `original`, `min`
+//! and `max`. Input starting with an operator applies it to the current
+//! value, so `+100` is `value + 100` and `*2` is `value * 2`.
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ExpressionError {
+    #[error("Expected a number or a name at position {0}")]
+    ExpectedOperand(usize),
+    #[error("Unexpected `{1}` at position {0}")]
+    Unexpected(usize, char),
+    #[error("Unknown name `{0}`, expected value, original, min or max")]
+    UnknownName(String),
+    #[error("Missing `)`")]
+    UnclosedParenthesis,
+    #[error("Division by zero")]
+    DivisionByZero,
+    #[error("The result doesn't fit in a number")]
+    Overflow,
+}
+
+/// Values the names in an expression refer to.
+#[derive(Clone, Copy, Debug)]
+pub struct Context {
+    pub value: u64,
+    pub original: u64,
+    pub min: u64,
+    pub max: u64,
+}
+
+impl Context {
+    fn name(&self, name: &str) -> Option<u64> {
+        match name {
+            "value" | "x" => Some(self.value),
+            "original" => Some(self.original),
+            "min" => Some(self.min),
+            "max" => Some(self.max),
+            _ => None,
+        }
+    }
+}
+
+/// Evaluates `input`. The result can be negative or out of bounds, checking
+/// that is left to the caller.
+pub fn evaluate(input: &str, context: &Context) -> Result<i128, ExpressionError> {
+    let input = input.trim();
+
+    let relative = input.starts_with(['+', '-', '*', '/']);
+    let source = if relative {
+        format!("value {input}")
+    } else {
+        input.to_string()
+    };
+
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        position: 0,
+        offset: source.chars().count() - input.chars().count(),
+        context,
+    };
+
+    let result = parser.expression()?;
+    parser.skip_whitespace();
+
+    match parser.peek() {
+        None => Ok(result),
+        Some(char) => Err(ExpressionError::Unexpected(parser.column(), char)),
+    }
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    position: usize,
+    /// Characters added in front of the user's input.
+    offset: usize,
+    context: &'a Context,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Position in the user's input, for error messages.
+    fn column(&self) -> usize {
+        self.position.saturating_sub(self.offset) + 1
+    }
+
+    fn expression(&mut self) -> Result<i128, ExpressionError> {
+        let mut result = self.term()?;
+
+        loop {
+            self.skip_whitespace();
+            let operator = match self.peek() {
+                Some(operator @ ('+' | '-')) => operator,
+                _ => return Ok(result),
+            };
+            self.position += 1;
+
+            let right = self.term()?;
+            result = match operator {
+                '+' => result.checked_add(right),
+                _ => result.checked_sub(right),
+            }
+            .ok_or(ExpressionError::Overflow)?;
+        }
+    }
+
+    fn term(&mut self) -> Result<i128, ExpressionError> {
+        let mut result = self.factor()?;
+
+        loop {
+            self.skip_whitespace();
+            let operator = match self.peek() {
+                Some(operator @ ('*' | '/')) => operator,
+                _ => return Ok(result),
+            };
+            self.position += 1;
+
+            let right = self.factor()?;
+            result = match operator {
+                '*' => result.checked_mul(right).ok_or(ExpressionError::Overflow)?,
+                _ if right == 0 => return Err(ExpressionError::DivisionByZero),
+                _ => result.checked_div(right).ok_or(ExpressionError::Overflow)?,
+            };
+        }
+    }
+
+    fn factor(&mut self) -> Result<i128, ExpressionError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('-') => {
+                self.position += 1;
+                self.factor()?
+                    .checked_neg()
+                    .ok_or(ExpressionError::Overflow)
+            }
+            Some('(') => {
+                self.position += 1;
+                let result = self.expression()?;
+                self.skip_whitespace();
+                if self.peek() != Some(')') {
+                    return Err(ExpressionError::UnclosedParenthesis);
+                }
+                self.position += 1;
+                Ok(result)
+            }
+            Some(char) if char.is_ascii_digit() => {
+                let digits = self.take_while(|x| x.is_ascii_digit() || x == '_');
+                digits
+                    .replace('_', "")
+                    .parse()
+                    .map_err(|_| ExpressionError::Overflow)
+            }
+            Some(char) if char.is_alphabetic() => {
+                let name = self.take_while(char::is_alphanumeric).to_lowercase();
+                self.context
+                    .name(&name)
+                    .map(i128::from)
+                    .ok_or(ExpressionError::UnknownName(name))
+            }
+            _ => Err(ExpressionError::ExpectedOperand(self.column())),
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTEXT: Context = Context {
+        value: 10,
+        original: 4,
+        min: 0,
+        max: 100,
+    };
+
+    fn eval(input: &str) -> Result<i128, ExpressionError> {
+        evaluate(input, &CONTEXT)
+    }
+
+    #[test]
+    fn multiplication_and_division_come_first() {
+        assert_eq!(eval("2 + 3 * 4"), Ok(14));
+        assert_eq!(eval("20 - 6 / 2"), Ok(17));
+        assert_eq!(eval("(2 + 3) * 4"), Ok(20));
+        assert_eq!(eval("10 - 2 - 3"), Ok(5));
+        assert_eq!(eval("7 / 2"), Ok(3));
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval("2 * -3"), Ok(-6));
+        assert_eq!(eval("(-5 + 8)"), Ok(3));
+        assert_eq!(eval("1 - -(1 + 2)"), Ok(4));
+        // A leading `-` subtracts from the value instead.
+        assert_eq!(eval("--4"), Ok(14));
+    }
+
+    #[test]
+    fn leading_operators_apply_to_the_value() {
+        assert_eq!(eval("+100"), Ok(110));
+        assert_eq!(eval("*2"), Ok(20));
+        assert_eq!(eval("/ 5"), Ok(2));
+        assert_eq!(eval("-3"), Ok(7));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(eval("min"), Ok(0));
+        assert_eq!(eval("MAX"), Ok(100));
+        assert_eq!(eval("original + x"), Ok(14));
+        assert_eq!(eval("(value + max) / 2"), Ok(55));
+        assert_eq!(eval("1_000"), Ok(1000));
+        assert_eq!(
+            eval("mix"),
+            Err(ExpressionError::UnknownName("mix".to_string()))
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let max = Context {
+            value: u64::MAX,
+            ..CONTEXT
+        };
+        assert_eq!(
+            evaluate("value * value * value", &max),
+            Err(ExpressionError::Overflow)
+        );
+        assert_eq!(
+            eval("999999999999999999999999999999999999999999"),
+            Err(ExpressionError::Overflow)
+        );
+        assert_eq!(eval("1 / 0"), Err(ExpressionError::DivisionByZero));
+
+        let min = "(-170141183460469231731687303715884105727 - 1)";
+        assert_eq!(eval(&format!("0 - {min}")), Err(ExpressionError::Overflow));
+        assert_eq!(eval(&format!("1 * -{min}")), Err(ExpressionError::Overflow));
+        assert_eq!(eval(&format!("{min} / -1")), Err(ExpressionError::Overflow));
+        assert_eq!(eval(&format!("{min} / 1")), Ok(i128::MIN));
+    }
+
+    #[test]
+    fn errors_point_at_the_users_input() {
+        assert_eq!(eval("1 +"), Err(ExpressionError::ExpectedOperand(4)));
+        assert_eq!(eval("1 + )"), Err(ExpressionError::ExpectedOperand(5)));
+        assert_eq!(eval("2 3"), Err(ExpressionError::Unexpected(3, '3')));
+        assert_eq!(eval("(1 + 2"), Err(ExpressionError::UnclosedParenthesis));
+        // Relative input gets `value ` put in front, which isn't counted.
+        assert_eq!(eval("+ 2 )"), Err(ExpressionError::Unexpected(5, ')')));
+        assert_eq!(eval("  * x"), Ok(100));
+        assert_eq!(eval("*"), Err(ExpressionError::ExpectedOperand(2)));
+    }
+}
@@ -0,0 +1,168 @@
+//! Addresses of single fields in a save, so edits can be described as plain
+//! data instead of closures.
+
+use std::{borrow::Cow, fmt::Display};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::models::SaveGame;
+
+/// Entry of the save a field belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Entry {
+    Save,
+    Level(usize),
+    Difficulty {
+        level: usize,
+        remix: bool,
+        index: usize,
+    },
+    Storyline(usize),
+    StoryBeat {
+        storyline: usize,
+        index: usize,
+    },
+    Enemy(usize),
+}
+
+/// A field of an entry, named as it is in the save file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FieldAddress {
+    pub entry: Entry,
+    pub field: Cow<'static, str>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    Bool(bool),
+    Number(u64),
+    Text(String),
+}
+
+#[derive(Debug, Error)]
+pub enum FieldError {
+    #[error("{0} doesn't exist")]
+    MissingEntry(Entry),
+    #[error("{0} doesn't have a field {1}")]
+    MissingField(Entry, String),
+    #[error("{0} can't be set to {1:?}")]
+    WrongType(FieldAddress, FieldValue),
+}
+
+impl FieldAddress {
+    pub fn new(entry: Entry, field: &'static str) -> Self {
+        Self {
+            entry,
+            field: Cow::Borrowed(field),
+        }
+    }
+
+    /// Sets the field in `save`. The value has to have the same type as the
+    /// field currently holds.
+    pub fn set(&self, save: &mut SaveGame, value: FieldValue) -> Result<(), FieldError> {
+        let missing = || FieldError::MissingEntry(self.entry);
+
+        match self.entry {
+            Entry::Save => self.set_in(save, value),
+            Entry::Level(index) => {
+                self.set_in(save.level_data.get_mut(index).ok_or_else(missing)?, value)
+            }
+            Entry::Difficulty {
+                level,
+                remix,
+                index,
+            } => {
+                let level = save.level_data.get_mut(level).ok_or_else(missing)?;
+                let data = if remix {
+                    &mut level.remix_difficulty_data
+                } else {
+                    &mut level.difficulty_data
+                };
+                self.set_in(data.get_mut(index).ok_or_else(missing)?, value)
+            }
+            Entry::Storyline(index) => self.set_in(
+                save.storyline_data.get_mut(index).ok_or_else(missing)?,
+                value,
+            ),
+            Entry::StoryBeat { storyline, index } => {
+                let storyline = save.storyline_data.get_mut(storyline).ok_or_else(missing)?;
+                self.set_in(
+                    storyline
+                        .story_beat_data
+                        .get_mut(index)
+                        .ok_or_else(missing)?,
+                    value,
+                )
+            }
+            Entry::Enemy(index) => self.set_in(
+                save.enemy_kill_counts_by_id
+                    .get_mut(index)
+                    .ok_or_else(missing)?,
+                value,
+            ),
+        }
+    }
+
+    fn set_in<T: Serialize + DeserializeOwned>(
+        &self,
+        target: &mut T,
+        value: FieldValue,
+    ) -> Result<(), FieldError> {
+        let wrong_type = || FieldError::WrongType(self.clone(), value.clone());
+
+        let mut json = serde_json::to_value(&*target).map_err(|_| wrong_type())?;
+        let field = json
+            .get_mut(self.field.as_ref())
+            .ok_or_else(|| FieldError::MissingField(self.entry, self.field.to_string()))?;
+
+        let new = match (&*field, &value) {
+            (Value::Bool(_), FieldValue::Bool(value)) => Value::from(*value),
+            (Value::Number(_), FieldValue::Number(value)) => Value::from(*value),
+            (Value::String(_), FieldValue::Text(value)) => Value::from(value.clone()),
+            _ => return Err(wrong_type()),
+        };
+        *field = new;
+
+        *target = serde_json::from_value(json).map_err(|_| wrong_type())?;
+        Ok(())
+    }
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Entry::Save => write!(f, "The save"),
+            Entry::Level(index) => write!(f, "LevelDatas[{index}]"),
+            Entry::Difficulty {
+                level,
+                remix: false,
+                index,
+            } => write!(f, "LevelDatas[{level}].DifficultyHighScoreDatas[{index}]"),
+            Entry::Difficulty {
+                level,
+                remix: true,
+                index,
+            } => write!(
+                f,
+                "LevelDatas[{level}].RemixDifficultyHighScoreDatas[{index}]"
+            ),
+            Entry::Storyline(index) => write!(f, "StorylineDatas[{index}]"),
+            Entry::StoryBeat { storyline, index } => {
+                write!(f, "StorylineDatas[{storyline}].StoryBeatDatas[{index}]")
+            }
+            Entry::Enemy(index) => write!(f, "EnemyKillCountsById[{index}]"),
+        }
+    }
+}
+
+impl Display for FieldAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.entry {
+            Entry::Save => write!(f, "{}", self.field),
+            entry => write!(f, "{entry}.{}", self.field),
+        }
+    }
+}
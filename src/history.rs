@@ -0,0 +1,71 @@
+const MAX_ENTRIES: usize = 256;
+
+struct Entry<T> {
+    snapshot: T,
+    key: Option<String>,
+}
+
+/// Undo/redo stacks of whole-document snapshots.
+///
+/// Each recorded entry holds the state from *before* an edit, so undoing is
+/// just swapping it with the current state.
+pub struct History<T> {
+    undo: Vec<Entry<T>>,
+    redo: Vec<Entry<T>>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl<T> History<T> {
+    /// Records `before` as an undo step. If `key` matches the key of the last
+    /// step, the edit is folded into it instead of creating a new one.
+    pub fn record(&mut self, before: T, key: Option<String>) {
+        self.redo.clear();
+
+        if key.is_some() && self.undo.last().is_some_and(|last| last.key == key) {
+            return;
+        }
+
+        if self.undo.len() == MAX_ENTRIES {
+            self.undo.remove(0);
+        }
+
+        self.undo.push(Entry {
+            snapshot: before,
+            key,
+        });
+    }
+
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let entry = self.undo.pop()?;
+        self.redo.push(Entry {
+            snapshot: current,
+            key: None,
+        });
+        Some(entry.snapshot)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let entry = self.redo.pop()?;
+        self.undo.push(Entry {
+            snapshot: current,
+            key: None,
+        });
+        Some(entry.snapshot)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
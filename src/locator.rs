@@ -0,0 +1,355 @@
+//! Finds the game's saves on this computer.
+//!
+//! On Windows the saves are in the user's `AppData\LocalLow`. On Linux the game
+//! runs through Proton, which keeps a Windows prefix per game inside the Steam
+//! library the game is installed in, so every library listed in Steam's
+//! `libraryfolders.vdf` is searched.
+
+use std::{
+    cmp::Reverse,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use self::vdf::Vdf;
+use crate::{
+    catalog,
+    models::SaveGame,
+    save_file::{self, SaveFileError},
+};
+
+/// Steam app ID of Rift of the NecroDancer.
+pub const APP_ID: u64 = 2073250;
+
+/// The saves folder, relative to a Windows user's home folder.
+const SAVES_FOLDER: &[&str] = &[
+    "AppData",
+    "LocalLow",
+    "Brace Yourself Games",
+    "Rift of the NecroDancer",
+    "Saves",
+];
+
+/// Steam installs checked on Linux, relative to the home folder: the native
+/// package, its older symlinks and the Flatpak.
+const STEAM_ROOTS: &[&str] = &[
+    ".local/share/Steam",
+    ".steam/steam",
+    ".steam/root",
+    ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+];
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FoundSave {
+    pub path: PathBuf,
+    /// Where the save was found, such as the Steam library of a Proton
+    /// prefix.
+    pub location: String,
+    pub modified: Option<SystemTime>,
+    /// What's in the save, or why it couldn't be read.
+    pub summary: Result<SaveSummary, String>,
+}
+
+/// The parts of a save that tell slots apart.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct SaveSummary {
+    pub save_name: String,
+    pub times_booted: u64,
+    /// Percentage, see [`catalog::completion`].
+    pub completion: f32,
+    pub diamonds: u64,
+}
+
+impl SaveSummary {
+    pub fn of(save: &SaveGame) -> Self {
+        Self {
+            save_name: save.save_name.clone(),
+            times_booted: save.times_booted,
+            completion: catalog::completion(save),
+            diamonds: save.total_diamonds,
+        }
+    }
+}
+
+/// Every save in the folders the game keeps them in, most recently changed
+/// first.
+pub fn find_saves() -> Vec<FoundSave> {
+    let mut saves = save_folders()
+        .into_iter()
+        .flat_map(|(folder, location)| saves_in(&folder, &location))
+        .collect::<Vec<_>>();
+
+    saves.sort_by_key(|x| Reverse(x.modified));
+    saves
+}
+
+/// Saves folders that exist, with a description of where they are.
+pub fn save_folders() -> Vec<(PathBuf, String)> {
+    let mut folders = Vec::new();
+
+    let saves_folder = SAVES_FOLDER.iter().collect::<PathBuf>();
+
+    if let Some(profile) = std::env::var_os("USERPROFILE").map(PathBuf::from) {
+        folders.push((profile.join(&saves_folder), "Windows".to_string()));
+    }
+
+    if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        for root in STEAM_ROOTS {
+            for library in libraries(&home.join(root)) {
+                let prefix = library
+                    .join("steamapps/compatdata")
+                    .join(APP_ID.to_string())
+                    .join("pfx/drive_c/users/steamuser");
+                folders.push((
+                    prefix.join(&saves_folder),
+                    format!("Proton prefix in {}", library.display()),
+                ));
+            }
+        }
+    }
+
+    folders.retain(|(folder, _)| folder.is_dir());
+    // The same library is usually reachable from several roots.
+    let mut unique: Vec<(PathBuf, String)> = Vec::new();
+    for folder in folders {
+        if !unique.iter().any(|x| same_file(&x.0, &folder.0)) {
+            unique.push(folder);
+        }
+    }
+    unique
+}
+
+/// Libraries of the Steam install at `root`, including the install itself.
+fn libraries(root: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![root.to_path_buf()];
+
+    let Ok(content) = std::fs::read_to_string(root.join("steamapps/libraryfolders.vdf")) else {
+        return libraries;
+    };
+
+    if let Some(Vdf::Object(folders)) = vdf::parse(&content).get("libraryfolders") {
+        for (_, folder) in folders {
+            // Older files map an index straight to the path, newer ones to
+            // an object with a `path` entry.
+            let path = match folder {
+                Vdf::Value(path) => Some(path),
+                Vdf::Object(_) => match folder.get("path") {
+                    Some(Vdf::Value(path)) => Some(path),
+                    _ => None,
+                },
+            };
+
+            if let Some(path) = path {
+                libraries.push(PathBuf::from(path));
+            }
+        }
+    }
+
+    libraries
+}
+
+fn saves_in(folder: &Path, location: &str) -> Vec<FoundSave> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|x| x.is_file()))
+        // Backups the editor makes next to the save.
+        .filter(|entry| !entry.file_name().to_string_lossy().ends_with(".bak"))
+        .map(|entry| FoundSave {
+            path: entry.path(),
+            location: location.to_string(),
+            modified: entry.metadata().and_then(|x| x.modified()).ok(),
+            summary: save_file::load(&entry.path())
+                .map(|(save, _)| SaveSummary::of(&save))
+                .map_err(|err| match err {
+                    SaveFileError::Parse(diagnostic) => diagnostic.summary(),
+                    err => err.to_string(),
+                }),
+        })
+        .collect()
+}
+
+/// Whether both paths lead to the same file, as the Steam root symlinks
+/// point at the same install.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Valve's KeyValues text format, as used by `libraryfolders.vdf`.
+mod vdf {
+    pub enum Vdf {
+        Value(String),
+        Object(Vec<(String, Vdf)>),
+    }
+
+    impl Vdf {
+        pub fn get(&self, key: &str) -> Option<&Vdf> {
+            match self {
+                Vdf::Object(entries) => entries
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                    .map(|(_, value)| value),
+                Vdf::Value(_) => None,
+            }
+        }
+    }
+
+    enum Token {
+        String(String),
+        Open,
+        Close,
+    }
+
+    fn tokens(content: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut chars = content.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            match char {
+                '{' => tokens.push(Token::Open),
+                '}' => tokens.push(Token::Close),
+                '"' => {
+                    let mut string = String::new();
+                    while let Some(char) = chars.next() {
+                        match char {
+                            '"' => break,
+                            '\\' => match chars.next() {
+                                Some('n') => string.push('\n'),
+                                Some('t') => string.push('\t'),
+                                Some(char) => string.push(char),
+                                None => {}
+                            },
+                            char => string.push(char),
+                        }
+                    }
+                    tokens.push(Token::String(string));
+                }
+                '/' if chars.peek() == Some(&'/') => {
+                    for char in chars.by_ref() {
+                        if char == '\n' {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        tokens
+    }
+
+    /// Parses the whole file as the entries of one object. Anything that
+    /// doesn't fit the format ends the object it's in.
+    pub fn parse(content: &str) -> Vdf {
+        let mut tokens = tokens(content).into_iter();
+        object(&mut tokens)
+    }
+
+    fn object(tokens: &mut impl Iterator<Item = Token>) -> Vdf {
+        let mut entries = Vec::new();
+
+        while let Some(Token::String(key)) = tokens.next() {
+            match tokens.next() {
+                Some(Token::String(value)) => entries.push((key, Vdf::Value(value))),
+                Some(Token::Open) => entries.push((key, object(tokens))),
+                _ => break,
+            }
+        }
+
+        Vdf::Object(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vdf::{self, Vdf};
+
+    fn value(vdf: Option<&Vdf>) -> Option<&str> {
+        match vdf {
+            Some(Vdf::Value(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn parses_library_folders() {
+        let content = r#"
+"libraryfolders"
+{
+	// A comment
+	"0"
+	{
+		"path"		"/home/user/.local/share/Steam"
+		"label"		""
+		"apps"
+		{
+			"2073250"		"1234"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\Games\\Steam"
+	}
+}
+"#;
+        let parsed = vdf::parse(content);
+        let folders = parsed.get("LibraryFolders").unwrap();
+
+        assert_eq!(
+            value(folders.get("0").and_then(|x| x.get("path"))),
+            Some("/home/user/.local/share/Steam")
+        );
+        assert_eq!(
+            value(folders.get("0").and_then(|x| x.get("label"))),
+            Some("")
+        );
+        assert_eq!(
+            value(
+                folders
+                    .get("0")
+                    .and_then(|x| x.get("apps"))
+                    .and_then(|x| x.get("2073250"))
+            ),
+            Some("1234")
+        );
+        assert_eq!(
+            value(folders.get("1").and_then(|x| x.get("path"))),
+            Some(r"D:\Games\Steam")
+        );
+    }
+
+    #[test]
+    fn parses_the_older_format() {
+        let content = r#""LibraryFolders" { "TimeNextStatsReport" "1" "1" "/mnt/games" }"#;
+        let parsed = vdf::parse(content);
+        let folders = parsed.get("libraryfolders").unwrap();
+
+        assert_eq!(value(folders.get("1")), Some("/mnt/games"));
+        assert!(folders.get("path").is_none());
+    }
+
+    #[test]
+    fn escapes() {
+        let parsed = vdf::parse(r#""a" "line\nnext\t\"quoted\"""#);
+        assert_eq!(value(parsed.get("a")), Some("line\nnext\t\"quoted\""));
+    }
+
+    #[test]
+    fn stops_at_broken_input() {
+        let parsed = vdf::parse(r#""a" "1" "b" { "c" "2" "#);
+        assert_eq!(value(parsed.get("a")), Some("1"));
+        assert_eq!(value(parsed.get("b").and_then(|x| x.get("c"))), Some("2"));
+
+        let parsed = vdf::parse(r#""a" } "b" "2""#);
+        assert!(parsed.get("a").is_none());
+        assert!(parsed.get("b").is_none());
+    }
+}
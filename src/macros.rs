@@ -0,0 +1,224 @@
+//! Edits recorded from a session, saved to a file and replayed onto another
+//! save.
+//!
+//! Editor messages point at entries by their position, which means nothing in
+//! a different save. Every step therefore also remembers what the entry it
+//! touched is, such as a level ID or a difficulty, and is pointed at wherever
+//! that entry is when it's replayed.
+
+use std::{fmt::Display, path::Path};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{field::Entry, models::SaveGame, views::editor::EditorMessage};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Macro {
+    pub steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Step {
+    /// Entry the message is about, if it's about one.
+    pub target: Option<Target>,
+    pub message: EditorMessage,
+}
+
+/// An entry of a save, identified the same way changes are matched between
+/// saves.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Target {
+    Save,
+    Level(String),
+    Difficulty {
+        level: String,
+        remix: bool,
+        difficulty: u64,
+    },
+    Storyline(u64),
+    StoryBeat {
+        storyline: u64,
+        level: String,
+    },
+    Enemy(u64),
+}
+
+/// A step that couldn't be replayed.
+#[derive(Clone, Debug)]
+pub struct Skipped {
+    /// Position of the step in the macro, starting at 1.
+    pub step: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Error)]
+pub enum MacroError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Error parsing macro: {0}")]
+    Parse(#[source] serde_json::Error),
+    #[error("Serialization error: {0}")]
+    Serialize(#[source] serde_json::Error),
+}
+
+impl Step {
+    /// Describes `message` as it applies to `save`. Returns `None` if the
+    /// message refers to an entry `save` doesn't have.
+    pub fn new(message: EditorMessage, save: &SaveGame) -> Option<Self> {
+        let target = match message.entry() {
+            Some(entry) => Some(Target::of(entry, save)?),
+            None => None,
+        };
+
+        Some(Self { target, message })
+    }
+
+    /// The message pointed at where the target is in `save`.
+    pub fn resolve(&self, save: &SaveGame) -> Result<EditorMessage, String> {
+        match &self.target {
+            Some(target) => target
+                .find(save)
+                .map(|entry| self.message.clone().with_entry(entry))
+                .ok_or_else(|| format!("{target} doesn't exist")),
+            None => Ok(self.message.clone()),
+        }
+    }
+}
+
+impl Target {
+    pub fn of(entry: Entry, save: &SaveGame) -> Option<Self> {
+        Some(match entry {
+            Entry::Save => Target::Save,
+            Entry::Level(index) => Target::Level(save.level_data.get(index)?.level_id.clone()),
+            Entry::Difficulty {
+                level,
+                remix,
+                index,
+            } => {
+                let level = save.level_data.get(level)?;
+                let data = if remix {
+                    &level.remix_difficulty_data
+                } else {
+                    &level.difficulty_data
+                };
+
+                Target::Difficulty {
+                    level: level.level_id.clone(),
+                    remix,
+                    difficulty: data.get(index)?.difficulty,
+                }
+            }
+            Entry::Storyline(index) => {
+                Target::Storyline(save.storyline_data.get(index)?.storyline_characters)
+            }
+            Entry::StoryBeat { storyline, index } => {
+                let storyline = save.storyline_data.get(storyline)?;
+
+                Target::StoryBeat {
+                    storyline: storyline.storyline_characters,
+                    level: storyline.story_beat_data.get(index)?.level_id.clone(),
+                }
+            }
+            Entry::Enemy(index) => Target::Enemy(save.enemy_kill_counts_by_id.get(index)?.enemy_id),
+        })
+    }
+
+    /// Where the target is in `save`, if it has it.
+    pub fn find(&self, save: &SaveGame) -> Option<Entry> {
+        let level_index =
+            |level_id: &str| save.level_data.iter().position(|x| x.level_id == level_id);
+        let storyline_index = |characters: u64| {
+            save.storyline_data
+                .iter()
+                .position(|x| x.storyline_characters == characters)
+        };
+
+        Some(match self {
+            Target::Save => Entry::Save,
+            Target::Level(level_id) => Entry::Level(level_index(level_id)?),
+            Target::Difficulty {
+                level,
+                remix,
+                difficulty,
+            } => {
+                let level = level_index(level)?;
+                let data = if *remix {
+                    &save.level_data[level].remix_difficulty_data
+                } else {
+                    &save.level_data[level].difficulty_data
+                };
+
+                Entry::Difficulty {
+                    level,
+                    remix: *remix,
+                    index: data.iter().position(|x| x.difficulty == *difficulty)?,
+                }
+            }
+            Target::Storyline(characters) => Entry::Storyline(storyline_index(*characters)?),
+            Target::StoryBeat {
+                storyline,
+                level: level_id,
+            } => {
+                let storyline = storyline_index(*storyline)?;
+
+                Entry::StoryBeat {
+                    storyline,
+                    index: save.storyline_data[storyline]
+                        .story_beat_data
+                        .iter()
+                        .position(|x| &x.level_id == level_id)?,
+                }
+            }
+            Target::Enemy(enemy_id) => Entry::Enemy(
+                save.enemy_kill_counts_by_id
+                    .iter()
+                    .position(|x| x.enemy_id == *enemy_id)?,
+            ),
+        })
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Save => write!(f, "The save"),
+            Target::Level(level_id) => write!(f, "Level {level_id}"),
+            Target::Difficulty {
+                level,
+                remix: false,
+                difficulty,
+            } => write!(f, "Difficulty {difficulty} of level {level}"),
+            Target::Difficulty {
+                level,
+                remix: true,
+                difficulty,
+            } => write!(f, "Remix difficulty {difficulty} of level {level}"),
+            Target::Storyline(characters) => write!(f, "Storyline {characters}"),
+            Target::StoryBeat { storyline, level } => {
+                write!(f, "Story beat {level} of storyline {storyline}")
+            }
+            Target::Enemy(enemy_id) => write!(f, "Enemy {enemy_id}"),
+        }
+    }
+}
+
+impl Display for Skipped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Step {}: {}", self.step, self.reason)
+    }
+}
+
+pub fn load(path: &Path) -> Result<Macro, MacroError> {
+    let content = std::fs::read_to_string(path)?;
+
+    serde_json::from_str(&content).map_err(MacroError::Parse)
+}
+
+pub fn write(path: &Path, recorded: &Macro) -> Result<(), MacroError> {
+    let content = serde_json::to_string_pretty(recorded).map_err(MacroError::Serialize)?;
+
+    std::fs::write(path, content)?;
+
+    Ok(())
+}
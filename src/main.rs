@@ -1,100 +1,632 @@
-use std::{path::PathBuf, sync::Arc};
+use std::path::{Path, PathBuf};
 
-use iced::{Element, Task, widget::stack};
+use cli::Args;
+use config::{Config, Session};
+use iced::{
+    Element, Event, Subscription, Task, event,
+    keyboard::{self, Key, key::Named},
+    time,
+    widget::{column, stack, text_input},
+    window,
+};
 use modals::{
     Modal,
+    dialog::DialogState,
     numeric_field_editor::{
         NumericFieldEditorInit, NumericFieldEditorMessage, NumericFieldEditorState,
     },
+    picker::{PickerInit, PickerMessage, PickerState},
 };
 use models::SaveGame;
+use save_file::Encoding;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use views::{
     View,
     editor::{EditorMessage, EditorState},
+    generic::{GenericMessage, GenericState},
     pick_file::PickFileMessage,
 };
 
 include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
 
+pub mod catalog;
+mod cli;
+pub mod config;
+pub mod diagnostic;
+pub mod diff;
+pub mod expression;
+pub mod field;
+pub mod history;
+pub mod locator;
+pub mod macros;
 pub mod modals;
 pub mod models;
+pub mod recovery;
+pub mod save_file;
+pub mod schema;
+pub mod templates;
+pub mod versions;
 mod views;
 
 #[derive(Default)]
 struct Application {
+    tabs: Vec<Tab>,
+    /// Index into `tabs` of the one shown. There is always at least one tab.
+    active: usize,
+    /// ID given to the next tab opened.
+    next_id: TabId,
+    config: Config,
+}
+
+/// Identifies a tab for as long as it's open, unlike its position, which
+/// changes as tabs before it are closed.
+pub type TabId = u64;
+
+/// A save being edited, or the screen to pick one.
+#[derive(Default)]
+struct Tab {
+    id: TabId,
     view: View,
-    modal: Option<Modal>,
+    /// Open modals, the last one is on top.
+    modals: Vec<Modal>,
+    /// Path given through `--output`, used instead of the loaded file's
+    /// own path as the editor's save target.
+    output: Option<PathBuf>,
+    /// Session brought back once its save has loaded.
+    restore: Option<Session>,
+}
+
+impl Tab {
+    /// Puts `modal` on top of the others, focusing its text input if it has
+    /// one.
+    fn open_modal(&mut self, modal: Modal) -> Task<Message> {
+        let focus = matches!(modal, Modal::EditNumericField(_) | Modal::Pick(_));
+        self.modals.push(modal);
+
+        if focus {
+            text_input::focus(modals::input_id())
+        } else {
+            Task::none()
+        }
+    }
+
+    fn is_dirty(&self) -> bool {
+        match &self.view {
+            View::Editor(state) => state.is_dirty(),
+            View::Generic(state) => state.is_dirty(),
+            View::PickFile(_) => false,
+        }
+    }
+
+    /// Label of the tab in the tab bar.
+    fn title(&self) -> String {
+        let name = match &self.view {
+            View::Editor(state) => state.file_name(),
+            View::Generic(state) => state.file_name(),
+            View::PickFile(_) => return "Open Save".into(),
+        };
+
+        if self.is_dirty() {
+            format!("* {name}")
+        } else {
+            name
+        }
+    }
 }
 
 impl Application {
+    /// Adds `tab` after the others and shows it.
+    fn open_tab(&mut self, tab: Tab) -> TabId {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.tabs.push(Tab { id, ..tab });
+        self.active = self.tabs.len() - 1;
+        id
+    }
+
+    /// Removes the tab at `index`, opening an empty one if it was the last.
+    fn close_tab(&mut self, index: usize) -> Task<Message> {
+        self.tabs.remove(index);
+
+        if self.tabs.is_empty() {
+            return self.update(Message::NewTab);
+        }
+
+        if index < self.active || self.active >= self.tabs.len() {
+            self.active -= 1;
+        }
+        self.update(Message::SelectTab(self.tabs[self.active].id))
+    }
+
+    /// Adds a newly opened save to the recent ones.
+    fn remember(&mut self, path: &Path) {
+        self.config.add_recent(path);
+        self.save_config();
+    }
+
+    fn save_config(&self) {
+        if let Err(err) = self.config.save() {
+            tracing::warn!("Failed to save the config: {}", err);
+        }
+    }
+
+    /// Keeps where each open save was left, to reopen them next time.
+    fn save_sessions(&mut self) {
+        self.config.sessions = self
+            .tabs
+            .iter()
+            .filter_map(|tab| match &tab.view {
+                View::Editor(state) => Some(Session {
+                    path: std::path::absolute(state.path())
+                        .unwrap_or_else(|_| state.path().clone()),
+                    editor: state.session(),
+                }),
+                _ => None,
+            })
+            .collect();
+        self.save_config();
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::None => Task::none(),
-            Message::Init => Task::none(),
-            Message::PickFile(message) => match &mut self.view {
+            Message::Tab(id, message) => self.update_tab(id, *message),
+            Message::NewTab => {
+                let id = self.open_tab(Tab::default());
+                Task::done(PickFileMessage::Discover.into()).map(move |x: Message| x.for_tab(id))
+            }
+            Message::SelectTab(id) => {
+                let Some(index) = self.tabs.iter().position(|x| x.id == id) else {
+                    return Task::none();
+                };
+
+                self.active = index;
+                match &self.tabs[index].view {
+                    View::Editor(state) => state.snap_scroll(),
+                    _ => Task::none(),
+                }
+            }
+            Message::CloseRequested(id) => {
+                self.save_sessions();
+                Task::done(Message::RequestAction(PendingAction::CloseWindow(id)))
+            }
+            // Each tab with unsaved changes is asked about in turn, and
+            // closed once they're saved or discarded.
+            Message::RequestAction(PendingAction::CloseWindow(window)) => {
+                match self.tabs.iter().position(Tab::is_dirty) {
+                    Some(index) => {
+                        let action = PendingAction::CloseWindow(window);
+                        self.update_tab(self.tabs[index].id, Message::RequestAction(action))
+                    }
+                    None => {
+                        for tab in &self.tabs {
+                            if let View::Editor(state) = &tab.view {
+                                state.remove_recovery();
+                            }
+                        }
+                        window::close(window)
+                    }
+                }
+            }
+            Message::WriteRecovery => {
+                for tab in &mut self.tabs {
+                    if let View::Editor(state) = &mut tab.view {
+                        state.write_recovery();
+                    }
+                }
+                Task::none()
+            }
+            Message::SetReopenLast(value) => {
+                self.config.reopen_last = value;
+                self.save_config();
+                Task::none()
+            }
+            // Everything else comes from the tab on screen.
+            message => self.update_tab(self.tabs[self.active].id, message),
+        }
+    }
+
+    /// Handles a message for the tab `id`. Messages the tab's tasks send
+    /// back go to the same tab.
+    fn update_tab(&mut self, id: TabId, message: Message) -> Task<Message> {
+        // Messages for a tab that has been closed in the meantime are
+        // dropped.
+        let Some(index) = self.tabs.iter().position(|x| x.id == id) else {
+            return Task::none();
+        };
+
+        self.update_tab_at(index, message)
+            .map(move |message| message.for_tab(id))
+    }
+
+    fn update_tab_at(&mut self, index: usize, message: Message) -> Task<Message> {
+        // Unsaved changes are asked about on their own tab, which may not be
+        // the one shown, such as when closing a tab in the background.
+        if matches!(message, Message::RequestAction(_)) && self.tabs[index].is_dirty() {
+            self.active = index;
+        }
+
+        let tab = &mut self.tabs[index];
+
+        match message {
+            Message::Init => Task::done(PickFileMessage::Discover.into()),
+            // Messages for a view or modal that has been closed in the
+            // meantime are dropped.
+            Message::PickFile(message) => match &mut tab.view {
                 View::PickFile(state) => state.update(message),
-                _ => unreachable!(),
+                _ => Task::none(),
             },
-            Message::Editor(message) => match &mut self.view {
+            Message::Editor(message) => match &mut tab.view {
                 View::Editor(state) => state.update(message),
-                _ => unreachable!(),
+                _ => Task::none(),
             },
-            Message::Loaded(save_game, path) => {
-                self.view = View::Editor(EditorState::new(save_game, path));
+            Message::Generic(message) => match &mut tab.view {
+                View::Generic(state) => state.update(message),
+                _ => Task::none(),
+            },
+            Message::Loaded(save_game, path, encoding) => {
+                let restore = tab.restore.take().filter(|x| x.path == path);
+                let target = tab.output.take().unwrap_or(path.clone());
+
+                let mut state = EditorState::new(save_game, target, encoding);
+                let mut task = Task::none();
+                if let Some(session) = restore {
+                    state.restore(session.editor);
+                    if index == self.active {
+                        task = state.snap_scroll();
+                    }
+                }
+
+                if let Some(snapshot) = recovery::load(state.path()) {
+                    state.hold_recovery();
+                    task = task
+                        .chain(tab.open_modal(Modal::Dialog(modals::recovery::dialog(snapshot))));
+                }
+
+                tab.view = View::Editor(state);
+                self.remember(&path);
+                task
+            }
+            Message::LoadedGeneric(value, error, path, encoding) => {
+                tab.restore = None;
+
+                let target = tab.output.take().unwrap_or(path.clone());
+                tab.view = View::Generic(GenericState::new(value, error, target, encoding));
+                self.remember(&path);
                 Task::none()
             }
-            Message::NumericEditor(message) => match &mut self.modal {
+            Message::NumericEditor(message) => match tab.modals.last_mut() {
                 Some(Modal::EditNumericField(state)) => state.update(message),
-                _ => unreachable!(),
+                _ => Task::none(),
             },
             Message::OpenNumericEditor(init) => {
-                let init = match Arc::try_unwrap(init) {
-                    Ok(x) => x,
-                    Err(_) => return Task::none(),
+                tab.open_modal(Modal::EditNumericField(NumericFieldEditorState::new(init)))
+            }
+            Message::Picker(message) => match tab.modals.last_mut() {
+                Some(Modal::Pick(state)) => state.update(message),
+                _ => Task::none(),
+            },
+            Message::OpenPicker(init) => {
+                let View::Editor(state) = &self.tabs[index].view else {
+                    return Task::none();
                 };
 
-                self.modal = Some(Modal::EditNumericField(NumericFieldEditorState::new(init)));
+                // Values from the saves open in other tabs are offered too.
+                let mut saves = state.saves().to_vec();
+                saves.extend(
+                    self.tabs
+                        .iter()
+                        .enumerate()
+                        .filter(|(position, _)| *position != index)
+                        .filter_map(|(_, tab)| match &tab.view {
+                            View::Editor(state) => Some(state.data()),
+                            _ => None,
+                        }),
+                );
+                let options = catalog::options(init.kind, &saves);
 
-                Task::none()
+                self.tabs[index].open_modal(Modal::Pick(PickerState::new(init, options)))
             }
+            Message::OpenDialog(dialog) => tab.open_modal(Modal::Dialog(dialog)),
+            Message::SubmitModal => match tab.modals.last_mut() {
+                Some(Modal::EditNumericField(state)) => {
+                    state.update(NumericFieldEditorMessage::Save)
+                }
+                Some(Modal::Pick(state)) => state.update(PickerMessage::Submit),
+                Some(Modal::Dialog(state)) => Task::done(state.submit()),
+                None => Task::none(),
+            },
             Message::CloseModal => {
-                self.modal = None;
+                tab.modals.pop();
+                Task::none()
+            }
+            Message::DismissModal => {
+                if tab.modals.last().is_some_and(Modal::is_dismissable) {
+                    tab.modals.pop();
+                }
                 Task::none()
             }
+            Message::CloseModalThen(message) => {
+                tab.modals.pop();
+                let id = tab.id;
+                self.update(message.for_tab(id))
+            }
+            Message::RequestAction(action) => match &tab.view {
+                View::Editor(state) if state.is_dirty() => {
+                    let dialog = modals::unsaved_changes::dialog(state.changes(), action);
+                    tab.open_modal(Modal::Dialog(dialog))
+                }
+                View::Generic(state) if state.is_dirty() => {
+                    let dialog = modals::unsaved_changes::dialog(state.changes(), action);
+                    tab.open_modal(Modal::Dialog(dialog))
+                }
+                _ => Task::done(Message::PerformAction(action)),
+            },
+            Message::SaveAndPerformAction(action) => {
+                let (task, dirty) = match &mut tab.view {
+                    View::Editor(state) => (state.update(EditorMessage::Save), state.is_dirty()),
+                    View::Generic(state) => (state.update(GenericMessage::Save), state.is_dirty()),
+                    View::PickFile(_) => return Task::done(Message::PerformAction(action)),
+                };
+
+                // A failed save leaves the editor dirty and its error on screen.
+                if dirty {
+                    return task;
+                }
+
+                task.chain(Task::done(Message::PerformAction(action)))
+            }
+            Message::PerformAction(action) => {
+                // The edits were saved or the user chose to lose them.
+                if let View::Editor(state) = &tab.view {
+                    state.remove_recovery();
+                }
+
+                match action {
+                    // Goes on with the other tabs.
+                    PendingAction::CloseWindow(window) => {
+                        self.close_tab(index)
+                            .chain(Task::done(Message::RequestAction(
+                                PendingAction::CloseWindow(window),
+                            )))
+                    }
+                    PendingAction::CloseSave => {
+                        tab.modals.clear();
+                        tab.view = View::default();
+                        Task::done(PickFileMessage::Discover.into())
+                    }
+                    PendingAction::CloseTab => self.close_tab(index),
+                }
+            }
+            // Messages for the whole app.
+            message => self.update(message),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match &self.tabs[self.active].view {
+            View::Editor(state) if state.is_dirty() => format!(
+                "* {} ({} unsaved) | Rift Of The Necrodancer | Save Editor",
+                state.file_name(),
+                state.changes()
+            ),
+            View::Editor(state) => format!(
+                "{} | Rift Of The Necrodancer | Save Editor",
+                state.file_name()
+            ),
+            View::Generic(state) if state.is_dirty() => format!(
+                "* {} ({} unsaved) | Rift Of The Necrodancer | Save Editor",
+                state.file_name(),
+                state.changes()
+            ),
+            View::Generic(state) => format!(
+                "{} | Rift Of The Necrodancer | Save Editor",
+                state.file_name()
+            ),
+            View::PickFile(_) => "Rift Of The Necrodancer | Save Editor".into(),
         }
     }
 
     pub fn view(&self) -> Element<Message> {
-        let view = match &self.view {
-            View::PickFile(state) => views::pick_file::view(state),
+        let tab = &self.tabs[self.active];
+        let id = tab.id;
+
+        let view = match &tab.view {
+            View::PickFile(state) => views::pick_file::view(state, &self.config),
             View::Editor(state) => views::editor::view(state),
+            View::Generic(state) => views::generic::view(state),
         };
 
-        let modal = match self.modal.as_ref() {
-            Some(Modal::EditNumericField(state)) => {
-                Some(modals::numeric_field_editor::view(&state))
-            }
-            None => None,
+        let modals = tab.modals.iter().map(|modal| match modal {
+            Modal::EditNumericField(state) => modals::numeric_field_editor::view(state),
+            Modal::Pick(state) => modals::picker::view(state),
+            Modal::Dialog(state) => modals::dialog::view(state),
+        });
+
+        let tabs = self.tabs.iter().map(|tab| (tab.id, tab.title()));
+
+        column![
+            views::tab_bar::view(tabs, id),
+            Element::from(stack(std::iter::once(view).chain(modals)))
+                .map(move |message| message.for_tab(id))
+        ]
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let close_requests = window::close_requests().map(Message::CloseRequested);
+        let tab = &self.tabs[self.active];
+
+        let shortcuts = match (tab.modals.is_empty(), &tab.view) {
+            (false, _) => event::listen_with(|event, _status, _window| match event {
+                Event::Keyboard(keyboard::Event::KeyPressed {
+                    key: Key::Named(Named::Enter),
+                    ..
+                }) => Some(Message::SubmitModal),
+                Event::Keyboard(keyboard::Event::KeyPressed {
+                    key: Key::Named(Named::Escape),
+                    ..
+                }) => Some(Message::DismissModal),
+                _ => None,
+            }),
+            (true, View::Editor(_)) => event::listen_with(|event, _status, _window| {
+                let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event
+                else {
+                    return None;
+                };
+
+                let Key::Character(character) = key.as_ref() else {
+                    return None;
+                };
+
+                if !modifiers.command() {
+                    return None;
+                }
+
+                match character.to_lowercase().as_str() {
+                    "z" if modifiers.shift() => Some(EditorMessage::Redo.into()),
+                    "z" => Some(EditorMessage::Undo.into()),
+                    "y" => Some(EditorMessage::Redo.into()),
+                    _ => None,
+                }
+            }),
+            _ => Subscription::none(),
         };
 
-        stack([view]).push_maybe(modal).into()
+        let editing = self
+            .tabs
+            .iter()
+            .any(|tab| matches!(tab.view, View::Editor(_)));
+        let recovery = match editing {
+            true => time::every(recovery::INTERVAL).map(|_| Message::WriteRecovery),
+            false => Subscription::none(),
+        };
+
+        Subscription::batch([close_requests, shortcuts, recovery])
     }
 
-    pub fn init() -> (Application, Task<Message>) {
-        (Default::default(), Task::done(Message::Init))
+    pub fn init(args: Args) -> (Application, Task<Message>) {
+        let mut application = Application {
+            config: Config::load(),
+            ..Default::default()
+        };
+
+        // A save given on the command line replaces the last sessions.
+        let mut tabs = match &args.input {
+            Some(input) => {
+                let tab = Tab {
+                    output: args.output,
+                    ..Default::default()
+                };
+                vec![(Some(input.clone()), tab)]
+            }
+            None => application
+                .config
+                .last_sessions()
+                .into_iter()
+                .map(|session| {
+                    let tab = Tab {
+                        restore: Some(session.clone()),
+                        ..Default::default()
+                    };
+                    (Some(session.path), tab)
+                })
+                .collect(),
+        };
+        if tabs.is_empty() {
+            tabs.push((None, Tab::default()));
+        }
+
+        let mut tasks = Vec::new();
+        for (input, tab) in tabs {
+            let mut task = Task::done(Message::Init);
+            if let Some(input) = input {
+                let path = input.to_string_lossy().to_string();
+                task = task
+                    .chain(Task::done(PickFileMessage::UserChangedPath(path).into()))
+                    .chain(Task::done(PickFileMessage::Submit.into()));
+            }
+
+            let id = application.open_tab(tab);
+            tasks.push(task.map(move |message| message.for_tab(id)));
+        }
+        application.active = 0;
+
+        (application, Task::batch(tasks))
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Message {
     None,
     Init,
-    Loaded(SaveGame, PathBuf),
+    /// A message for the tab with this ID. Messages without one go to the
+    /// tab on screen.
+    Tab(TabId, Box<Message>),
+    /// Opens a tab to pick another save in.
+    NewTab,
+    SelectTab(TabId),
+    Loaded(SaveGame, PathBuf, Encoding),
+    /// A save that is valid JSON but doesn't parse as `SaveGame`, with the
+    /// error from trying.
+    LoadedGeneric(Value, String, PathBuf, Encoding),
+    Generic(GenericMessage),
     PickFile(PickFileMessage),
     Editor(EditorMessage),
     NumericEditor(NumericFieldEditorMessage),
-    OpenNumericEditor(Arc<NumericFieldEditorInit>),
+    OpenNumericEditor(NumericFieldEditorInit),
+    Picker(PickerMessage),
+    OpenPicker(PickerInit),
+    OpenDialog(DialogState),
+    /// Enter on the modal on top: saves, picks or presses the last button.
+    SubmitModal,
+    /// Closes the modal on top.
     CloseModal,
+    /// Closes the modal on top if it doesn't have to be answered, for Escape
+    /// and clicks next to it.
+    DismissModal,
+    /// Closes the modal on top, then handles the message.
+    CloseModalThen(Box<Message>),
+    /// Window IDs only mean something while the app is running, so this
+    /// can't be serialized.
+    #[serde(skip)]
+    CloseRequested(window::Id),
+    /// Asks the user what to do with unsaved changes before running the action.
+    RequestAction(PendingAction),
+    SaveAndPerformAction(PendingAction),
+    PerformAction(PendingAction),
+    /// Whether the saves open on close are opened again on start.
+    SetReopenLast(bool),
+    /// Brings the recovery snapshot of the open save up to date.
+    WriteRecovery,
+}
+
+impl Message {
+    /// Addresses the message to the tab `id`, unless it's for the whole app.
+    fn for_tab(self, id: TabId) -> Message {
+        match self {
+            Message::None
+            | Message::Tab(..)
+            | Message::NewTab
+            | Message::SelectTab(_)
+            | Message::CloseRequested(_)
+            | Message::RequestAction(PendingAction::CloseWindow(_))
+            | Message::SetReopenLast(_)
+            | Message::WriteRecovery => self,
+            message => Message::Tab(id, Box::new(message)),
+        }
+    }
+}
+
+/// Something that would throw away the editor's unsaved changes.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum PendingAction {
+    #[serde(skip)]
+    CloseWindow(window::Id),
+    CloseSave,
+    CloseTab,
 }
 
 fn main() {
@@ -111,13 +643,56 @@ PROFILE = "{}""#,
         BuildInfo::PROFILE
     );
 
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if args.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
+    if let (Some(input), Some(target)) = (&args.input, args.migrate) {
+        let output = args.output.as_ref().unwrap_or(input);
+        if let Err(err) = cli::migrate(input, target, output) {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let (Some(input), true) = (&args.input, args.check) {
+        if let Err(err) = cli::check(input) {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let (Some(input), Some(path)) = (&args.input, &args.replay) {
+        let output = args.output.as_ref().unwrap_or(input);
+        if let Err(err) = cli::replay(input, path, output) {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     tracing_subscriber::fmt::init();
 
     tracing::info!("Starting");
 
-    if let Err(err) = iced::application(Application::init, Application::update, Application::view)
+    let boot = move || Application::init(args.clone());
+
+    if let Err(err) = iced::application(boot, Application::update, Application::view)
+        .subscription(Application::subscription)
+        .exit_on_close_request(false)
         .antialiasing(true)
-        .title("Rift Of The Necrodancer | Save Editor")
+        .title(Application::title)
         .run()
     {
         tracing::error!("Error running application: {}", err);
@@ -0,0 +1,123 @@
+use iced::{
+    Element,
+    widget::{button, column, row, text},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::Message;
+
+use super::overlay;
+
+/// A message with a row of buttons, used for alerts and confirmations.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DialogState {
+    pub title: String,
+    pub body: String,
+    pub buttons: Vec<DialogButton>,
+    /// Whether only the buttons close the dialog, not Escape or a click
+    /// next to it.
+    pub required: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DialogButton {
+    pub label: String,
+    pub style: DialogButtonStyle,
+    /// Sent after the dialog closes. Buttons without one only close it.
+    pub action: Option<Message>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum DialogButtonStyle {
+    Secondary,
+    Danger,
+    Success,
+}
+
+impl DialogState {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            buttons: Vec::new(),
+            required: false,
+        }
+    }
+
+    /// Makes the dialog close only through its buttons, for choices that
+    /// can't be put off.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// A dialog that can only be dismissed.
+    pub fn alert(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self::new(title, body).button("OK", DialogButtonStyle::Secondary, None)
+    }
+
+    /// A dialog asking whether to go ahead with `action`.
+    pub fn confirm(
+        title: impl Into<String>,
+        body: impl Into<String>,
+        confirm: impl Into<String>,
+        action: Message,
+    ) -> Self {
+        Self::new(title, body)
+            .button("Cancel", DialogButtonStyle::Secondary, None)
+            .button(confirm, DialogButtonStyle::Danger, Some(action))
+    }
+
+    pub fn button(
+        mut self,
+        label: impl Into<String>,
+        style: DialogButtonStyle,
+        action: Option<Message>,
+    ) -> Self {
+        self.buttons.push(DialogButton {
+            label: label.into(),
+            style,
+            action,
+        });
+        self
+    }
+
+    /// Message sent by Enter, which presses the last button.
+    pub fn submit(&self) -> Message {
+        self.buttons
+            .last()
+            .map(DialogButton::message)
+            .unwrap_or(Message::CloseModal)
+    }
+}
+
+impl DialogButton {
+    fn message(&self) -> Message {
+        match &self.action {
+            Some(action) => Message::CloseModalThen(Box::new(action.clone())),
+            None => Message::CloseModal,
+        }
+    }
+}
+
+pub fn view(state: &DialogState) -> Element<Message> {
+    let buttons = state.buttons.iter().map(|x| {
+        button(text(x.label.clone()))
+            .style(match x.style {
+                DialogButtonStyle::Secondary => button::secondary,
+                DialogButtonStyle::Danger => button::danger,
+                DialogButtonStyle::Success => button::success,
+            })
+            .on_press(x.message())
+            .into()
+    });
+
+    overlay(
+        column![
+            text(state.title.clone()).size(20.0),
+            text(state.body.clone()),
+            row(buttons).spacing(4.0)
+        ]
+        .spacing(8.0),
+    )
+}
@@ -1,7 +1,73 @@
+use dialog::DialogState;
+use iced::{
+    Background, Border, Element, Length, color,
+    widget::{container, mouse_area, opaque, text_input},
+};
 use numeric_field_editor::NumericFieldEditorState;
+use picker::PickerState;
 
+use crate::Message;
+
+pub mod dialog;
 pub mod numeric_field_editor;
+pub mod picker;
+pub mod recovery;
+pub mod unsaved_changes;
 
+/// Modals are kept in a stack, the last one is shown on top and receives
+/// keyboard input.
 pub enum Modal {
     EditNumericField(NumericFieldEditorState),
+    Pick(PickerState),
+    Dialog(DialogState),
+}
+
+impl Modal {
+    /// Whether Escape or a click on the backdrop closes the modal.
+    pub fn is_dismissable(&self) -> bool {
+        match self {
+            Modal::Dialog(state) => !state.required,
+            Modal::EditNumericField(_) | Modal::Pick(_) => true,
+        }
+    }
+}
+
+/// Text input of the modal on top, focused when it opens.
+pub fn input_id() -> text_input::Id {
+    text_input::Id::new("modal-input")
+}
+
+/// Shows `content` as a card over a dimmed backdrop. Clicking the backdrop
+/// dismisses the modal.
+pub fn overlay<'a>(content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    let card = container(content)
+        .style(|_| container::Style {
+            shadow: iced::Shadow {
+                color: color!(0x333333),
+                offset: iced::Vector { x: 4.0, y: 4.0 },
+                blur_radius: 8.0,
+            },
+            border: Border {
+                radius: 8.0.into(),
+                ..Default::default()
+            },
+            background: Some(Background::Color(color!(0xFFFFFF))),
+            ..Default::default()
+        })
+        .max_width(600.0)
+        .padding(8.0);
+
+    opaque(
+        mouse_area(
+            container(opaque(card))
+                .style(|_| container::Style {
+                    background: Some(Background::Color(color!(33, 33, 33, 0.3))),
+                    ..Default::default()
+                })
+                .center(Length::Fill)
+                .width(Length::Fill)
+                .height(Length::Fill),
+        )
+        .on_press(Message::DismissModal),
+    )
 }
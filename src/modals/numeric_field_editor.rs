@@ -1,35 +1,48 @@
+use std::ops::RangeInclusive;
+
 use iced::{
-    Background, Border, Element, Length, Task, color,
-    widget::{button, column, container, mouse_area, opaque, row, text, text_input},
+    Element, Task,
+    alignment::Vertical,
+    color,
+    widget::{button, column, container, row, text, text_input},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Message,
+    expression::{self, Context},
+    field::{FieldAddress, FieldValue},
+    views::editor::EditorMessage,
 };
 
-use crate::Message;
+use super::{input_id, overlay};
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct NumericFieldEditorInit {
     pub name: String,
     pub value: u64,
     pub original: u64,
-    pub on_save: Box<dyn Fn(u64) -> Message + Send + Sync + 'static>,
-}
-
-impl std::fmt::Debug for NumericFieldEditorInit {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "NumericFieldEditorInit")
-    }
+    /// Values the field accepts.
+    pub bounds: RangeInclusive<u64>,
+    /// Field the value is written to.
+    pub field: FieldAddress,
 }
 
 pub struct NumericFieldEditorState {
     pub name: String,
     pub value: u64,
     pub original: u64,
-    pub on_save: Box<dyn Fn(u64) -> Message + Send + Sync + 'static>,
+    pub bounds: RangeInclusive<u64>,
+    pub field: FieldAddress,
     pub input: String,
     pub error: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum NumericFieldEditorMessage {
     EditInput(String),
+    /// Adds the given amount to the value, staying within the bounds.
+    Step(i64),
     Save,
 }
 
@@ -39,97 +52,141 @@ impl NumericFieldEditorState {
             name: init.name,
             value: init.value,
             original: init.original,
-            on_save: init.on_save,
+            bounds: init.bounds,
+            field: init.field,
             input: init.value.to_string(),
             error: None,
         }
     }
 
+    fn context(&self) -> Context {
+        Context {
+            value: self.value,
+            original: self.original,
+            min: *self.bounds.start(),
+            max: *self.bounds.end(),
+        }
+    }
+
+    /// Value the input evaluates to, relative to the value the editor was
+    /// opened with.
+    fn evaluate(&self, input: &str) -> Result<u64, String> {
+        if input.trim().is_empty() {
+            return Err("Enter a value".to_string());
+        }
+
+        let result = expression::evaluate(input, &self.context()).map_err(|err| err.to_string())?;
+
+        let (min, max) = (*self.bounds.start(), *self.bounds.end());
+        if result < i128::from(min) {
+            return Err(format!("{} must be at least {min}", self.name));
+        }
+        if result > i128::from(max) {
+            return Err(format!("{} must be at most {max}", self.name));
+        }
+
+        Ok(result as u64)
+    }
+
+    /// The value the input currently evaluates to.
+    pub fn result(&self) -> Option<u64> {
+        self.evaluate(&self.input).ok()
+    }
+
     pub fn update(&mut self, message: NumericFieldEditorMessage) -> Task<Message> {
         match message {
-            NumericFieldEditorMessage::EditInput(value) => match value.parse::<u64>() {
-                Ok(valid) => {
-                    self.value = valid;
-                    self.error = None;
-                    self.input = value;
-                    Task::none()
-                }
-                Err(err) => {
-                    self.error = Some(format!("Invalid number: {}", err.to_string()));
-                    self.input = value;
-                    Task::none()
-                }
-            },
+            NumericFieldEditorMessage::EditInput(input) => {
+                self.error = self.evaluate(&input).err();
+                self.input = input;
+                Task::none()
+            }
+            NumericFieldEditorMessage::Step(amount) => {
+                let current = self.result().unwrap_or(self.value);
+                let stepped = current
+                    .saturating_add_signed(amount)
+                    .clamp(*self.bounds.start(), *self.bounds.end());
+
+                self.input = stepped.to_string();
+                self.error = None;
+                Task::none()
+            }
             NumericFieldEditorMessage::Save => {
-                if self.error.is_some() {
+                let Some(value) = self.result() else {
                     return Task::none();
-                }
+                };
 
-                let message = (self.on_save)(self.value);
+                let message =
+                    EditorMessage::SetField(self.field.clone(), FieldValue::Number(value)).into();
 
-                Task::batch([Task::done(message), Task::done(Message::CloseModal)])
+                Task::done(Message::CloseModalThen(Box::new(message)))
             }
         }
     }
 }
 
 pub fn view(state: &NumericFieldEditorState) -> Element<Message> {
-    opaque(mouse_area(
-        container(
-            container(
-                column![
-                    text(format!("Edit {}", state.name.clone())),
-                    row![
-                        text_input(&state.original.to_string(), &state.input.clone())
-                            .on_input(|x| NumericFieldEditorMessage::EditInput(x).into())
-                    ]
-                    .spacing(4.0),
-                    container(if let Some(error) = state.error.clone() {
-                        row![text(error).color(color!(0xFF3333))]
+    let step = |label: &'static str, amount: i64| {
+        button(label)
+            .style(button::secondary)
+            .on_press(NumericFieldEditorMessage::Step(amount).into())
+    };
+
+    // Shows what an expression comes out to when it isn't a plain number.
+    let preview = state
+        .result()
+        .filter(|result| state.input.trim() != result.to_string())
+        .map(|result| text(format!("= {result}")));
+
+    let bounds = text(format!(
+        "Between {} and {}",
+        state.bounds.start(),
+        state.bounds.end()
+    ))
+    .size(12.0)
+    .color(color!(0x999999));
+
+    overlay(
+        column![
+            text(format!("Edit {}", state.name.clone())),
+            row![
+                step("-10", -10),
+                step("-1", -1),
+                text_input(&state.original.to_string(), &state.input.clone())
+                    .id(input_id())
+                    .on_input(|x| NumericFieldEditorMessage::EditInput(x).into()),
+                step("+1", 1),
+                step("+10", 10),
+            ]
+            .push_maybe(preview)
+            .align_y(Vertical::Center)
+            .spacing(4.0),
+            text("Accepts numbers, +100, *2, max, original and (value + 10) / 2")
+                .size(12.0)
+                .color(color!(0x999999)),
+        ]
+        .push(bounds)
+        .push(container(if let Some(error) = state.error.clone() {
+            row![text(error).color(color!(0xFF3333))]
+        } else {
+            row![]
+        }))
+        .push(
+            row![
+                button("Cancel")
+                    .on_press(Message::CloseModal)
+                    .style(button::danger),
+                button("Save")
+                    .on_press_maybe(if state.error.is_none() {
+                        Some(NumericFieldEditorMessage::Save.into())
                     } else {
-                        row![]
-                    }),
-                    row![
-                        button("Cancel")
-                            .on_press(Message::CloseModal)
-                            .style(button::danger),
-                        button("Save")
-                            .on_press_maybe(if state.error.is_none() {
-                                Some(NumericFieldEditorMessage::Save.into())
-                            } else {
-                                None
-                            })
-                            .style(button::success)
-                    ]
-                    .spacing(4.0)
-                ]
-                .spacing(8.0),
-            )
-            .style(|_| container::Style {
-                shadow: iced::Shadow {
-                    color: color!(0x333333),
-                    offset: iced::Vector { x: 4.0, y: 4.0 },
-                    blur_radius: 8.0,
-                },
-                border: Border {
-                    radius: 8.0.into(),
-                    ..Default::default()
-                },
-                background: Some(Background::Color(color!(0xFFFFFF))),
-                ..Default::default()
-            })
-            .max_width(600.0)
-            .padding(8.0),
+                        None
+                    })
+                    .style(button::success)
+            ]
+            .spacing(4.0),
         )
-        .style(|_| container::Style {
-            background: Some(Background::Color(color!(33, 33, 33, 0.3))),
-            ..Default::default()
-        })
-        .center(Length::Fill)
-        .width(Length::Fill)
-        .height(Length::Fill),
-    ))
-    .into()
+        .spacing(8.0),
+    )
 }
 
 impl Into<Message> for NumericFieldEditorMessage {
@@ -0,0 +1,156 @@
+use iced::{
+    Element, Length, Task,
+    alignment::Vertical,
+    color,
+    widget::{button, column, row, scrollable, text, text_input},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Message,
+    catalog::{PickerKind, PickerOption},
+    field::{FieldAddress, FieldValue},
+    views::editor::EditorMessage,
+};
+
+use super::{input_id, overlay};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PickerInit {
+    pub name: String,
+    pub value: String,
+    pub kind: PickerKind,
+    /// Field the picked value is written to.
+    pub field: FieldAddress,
+}
+
+pub struct PickerState {
+    pub name: String,
+    pub value: String,
+    pub kind: PickerKind,
+    pub options: Vec<PickerOption>,
+    pub field: FieldAddress,
+    pub query: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum PickerMessage {
+    EditQuery(String),
+    Pick(String),
+    /// Picks the first option matching the search.
+    Submit,
+}
+
+impl PickerState {
+    pub fn new(init: PickerInit, options: Vec<PickerOption>) -> Self {
+        Self {
+            name: init.name,
+            value: init.value,
+            kind: init.kind,
+            options,
+            field: init.field,
+            query: String::new(),
+        }
+    }
+
+    /// Options matching the search by name or raw value, followed by the
+    /// search itself when it's a valid value that isn't listed.
+    pub fn visible(&self) -> Vec<PickerOption> {
+        let query = self.query.trim().to_lowercase();
+
+        let mut visible = self
+            .options
+            .iter()
+            .filter(|x| {
+                x.name.to_lowercase().contains(&query) || x.value.to_lowercase().contains(&query)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let custom = self.query.trim();
+        let valid = !self.kind.is_numeric() || custom.parse::<u64>().is_ok();
+        if !custom.is_empty() && valid && !self.options.iter().any(|x| x.value == custom) {
+            visible.push(PickerOption {
+                name: format!("Use \"{custom}\""),
+                value: custom.to_string(),
+            });
+        }
+
+        visible
+    }
+
+    pub fn update(&mut self, message: PickerMessage) -> Task<Message> {
+        let value = match message {
+            PickerMessage::EditQuery(query) => {
+                self.query = query;
+                return Task::none();
+            }
+            PickerMessage::Pick(value) => value,
+            PickerMessage::Submit => match self.visible().into_iter().next() {
+                Some(option) => option.value,
+                None => return Task::none(),
+            },
+        };
+
+        let value = if self.kind.is_numeric() {
+            match value.parse() {
+                Ok(value) => FieldValue::Number(value),
+                Err(_) => return Task::none(),
+            }
+        } else {
+            FieldValue::Text(value)
+        };
+
+        let message = EditorMessage::SetField(self.field.clone(), value).into();
+
+        Task::done(Message::CloseModalThen(Box::new(message)))
+    }
+}
+
+pub fn view(state: &PickerState) -> Element<Message> {
+    let options = state.visible().into_iter().map(|option| {
+        let selected = option.value == state.value;
+
+        button(
+            row![
+                text(option.name).width(Length::Fill),
+                text(option.value.clone())
+                    .size(12.0)
+                    .color(color!(0x999999))
+            ]
+            .align_y(Vertical::Center)
+            .spacing(8.0),
+        )
+        .style(if selected {
+            button::primary
+        } else {
+            button::secondary
+        })
+        .width(Length::Fill)
+        .on_press(PickerMessage::Pick(option.value).into())
+        .into()
+    });
+
+    overlay(
+        column![
+            text(format!("Pick {}", state.name.clone())),
+            text_input("Search...", &state.query)
+                .id(input_id())
+                .on_input(|x| PickerMessage::EditQuery(x).into()),
+            scrollable(column(options).spacing(2.0).padding(2.0)).height(Length::Fixed(320.0)),
+            row![
+                button("Cancel")
+                    .on_press(Message::CloseModal)
+                    .style(button::danger),
+            ]
+            .spacing(4.0)
+        ]
+        .spacing(8.0),
+    )
+}
+
+impl Into<Message> for PickerMessage {
+    fn into(self) -> Message {
+        Message::Picker(self)
+    }
+}
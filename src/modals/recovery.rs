@@ -0,0 +1,35 @@
+use crate::{
+    recovery::Snapshot,
+    views::{editor::EditorMessage, pick_file::ago},
+};
+
+use super::dialog::{DialogButtonStyle, DialogState};
+
+/// Offers to bring back the edits of `snapshot`, left behind when the app
+/// last closed without saving them. It has to be answered, so the snapshot
+/// isn't replaced by new edits before it was looked at.
+pub fn dialog(snapshot: Snapshot) -> DialogState {
+    let body = match snapshot.changes.len() {
+        1 => format!(
+            "There is 1 unsaved change from {} that wasn't saved or discarded. Restore it?",
+            ago(snapshot.written)
+        ),
+        changes => format!(
+            "There are {changes} unsaved changes from {} that weren't saved or discarded. Restore them?",
+            ago(snapshot.written)
+        ),
+    };
+
+    DialogState::new("Recover unsaved changes", body)
+        .button(
+            "Discard",
+            DialogButtonStyle::Danger,
+            Some(EditorMessage::DiscardRecovery.into()),
+        )
+        .button(
+            "Restore",
+            DialogButtonStyle::Success,
+            Some(EditorMessage::RestoreRecovery(snapshot.changes).into()),
+        )
+        .required()
+}
@@ -0,0 +1,31 @@
+use crate::{Message, PendingAction};
+
+use super::dialog::{DialogButtonStyle, DialogState};
+
+/// Asks whether to save, discard or keep `changes` unsaved changes before
+/// running `action`.
+pub fn dialog(changes: usize, action: PendingAction) -> DialogState {
+    let continue_label = match action {
+        PendingAction::CloseWindow(_) => "Quit without saving",
+        PendingAction::CloseSave => "Discard changes",
+        PendingAction::CloseTab => "Close without saving",
+    };
+
+    let body = match changes {
+        1 => "There is 1 unsaved change. Save it first?".to_string(),
+        changes => format!("There are {changes} unsaved changes. Save them first?"),
+    };
+
+    DialogState::new("Unsaved changes", body)
+        .button("Cancel", DialogButtonStyle::Secondary, None)
+        .button(
+            continue_label,
+            DialogButtonStyle::Danger,
+            Some(Message::PerformAction(action.clone())),
+        )
+        .button(
+            "Save",
+            DialogButtonStyle::Success,
+            Some(Message::SaveAndPerformAction(action)),
+        )
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct SaveGame {
     pub save_name: String,
@@ -42,9 +43,12 @@ pub struct SaveGame {
     pub bb_total_dodges: u64,
     #[serde(rename = "BBTotalBlockedHits")]
     pub bb_total_blocked_hits: u64,
+    /// Fields the editor doesn't know about, written back as they were.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct LevelData {
     pub level_id: String,
@@ -58,9 +62,12 @@ pub struct LevelData {
     pub difficulty_data: Vec<DifficultyData>,
     #[serde(rename = "RemixDifficultyHighScoreDatas")]
     pub remix_difficulty_data: Vec<DifficultyData>,
+    /// Fields the editor doesn't know about, written back as they were.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DifficultyData {
     pub difficulty: u64,
@@ -73,9 +80,12 @@ pub struct DifficultyData {
     pub num_game_overs: u64,
     pub has_all_perfects: bool,
     pub has_full_combo_rhythm_rift: bool,
+    /// Fields the editor doesn't know about, written back as they were.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct StorylineData {
     #[serde(rename = "storylineCharacters")]
@@ -84,19 +94,28 @@ pub struct StorylineData {
     pub has_completed_storyline: bool,
     #[serde(rename = "StoryBeatDatas")]
     pub story_beat_data: Vec<StoryBeatData>,
+    /// Fields the editor doesn't know about, written back as they were.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct StoryBeatData {
     pub level_id: String,
     pub times_played: u64,
+    /// Fields the editor doesn't know about, written back as they were.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct EnemyKillCount {
     pub enemy_id: u64,
     pub number_of_kills: u64,
     pub number_of_deaths: u64,
+    /// Fields the editor doesn't know about, written back as they were.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
@@ -0,0 +1,86 @@
+//! Snapshots of unsaved edits, written while editing so they aren't lost if
+//! the app dies. Each one is the list of changes from the save as it was
+//! loaded, kept in the config folder under a name made from the save's path.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{self, ConfigError},
+    diff::Change,
+};
+
+/// How often the open save's snapshot is brought up to date.
+pub const INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Snapshot {
+    /// Save the edits belong to.
+    pub path: PathBuf,
+    pub written: SystemTime,
+    pub changes: Vec<Change>,
+}
+
+/// Where the snapshot for the save at `path` is kept. Everything but letters
+/// and digits in the path becomes `_`, so each save gets its own file.
+fn file(path: &Path) -> Option<PathBuf> {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let name = path
+        .to_string_lossy()
+        .chars()
+        .map(|x| if x.is_alphanumeric() { x } else { '_' })
+        .collect::<String>();
+
+    config::dir().map(|x| x.join("recovery").join(format!("{name}.json")))
+}
+
+pub fn write(path: &Path, changes: &[Change]) -> Result<(), ConfigError> {
+    let file = file(path).ok_or(ConfigError::NoConfigDir)?;
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let snapshot = Snapshot {
+        path: path.to_path_buf(),
+        written: SystemTime::now(),
+        changes: changes.to_vec(),
+    };
+    std::fs::write(file, serde_json::to_string(&snapshot)?)?;
+    Ok(())
+}
+
+/// The snapshot left for the save at `path`, if it has any edits.
+pub fn load(path: &Path) -> Option<Snapshot> {
+    let content = std::fs::read_to_string(file(path)?).ok()?;
+
+    match serde_json::from_str::<Snapshot>(&content) {
+        Ok(snapshot) if !snapshot.changes.is_empty() => Some(snapshot),
+        Ok(_) => None,
+        Err(err) => {
+            tracing::warn!(
+                "Failed to parse the recovery snapshot of {}: {}",
+                path.display(),
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Deletes the snapshot for the save at `path`, once its edits were saved or
+/// thrown away.
+pub fn remove(path: &Path) {
+    let Some(file) = file(path) else {
+        return;
+    };
+
+    match std::fs::remove_file(&file) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => tracing::warn!("Failed to delete {}: {}", file.display(), err),
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{diagnostic::Diagnostic, models::SaveGame};
+
+#[derive(Debug, Error)]
+pub enum SaveFileError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Error parsing json: {0}")]
+    Parse(Box<Diagnostic>),
+    #[error("Serialization error: {0}")]
+    Serialize(#[source] serde_json::Error),
+    #[error("The file isn't valid {0} text")]
+    Decode(TextEncoding),
+}
+
+/// How a save file was stored, so it can be written back the same way.
+/// Unity saves copied between machines sometimes pick up a byte order mark,
+/// UTF-16 or NUL padding at the end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Encoding {
+    pub text: TextEncoding,
+    /// Whether the file starts with a byte order mark.
+    pub bom: bool,
+    /// Number of NUL bytes after the JSON.
+    pub padding: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Encoding {
+    /// Detects the encoding of `bytes` and decodes them, leaving out the byte
+    /// order mark and padding.
+    pub fn decode(bytes: &[u8]) -> Result<(String, Encoding), SaveFileError> {
+        let (text, bom, bytes) = match bytes {
+            [0xEF, 0xBB, 0xBF, rest @ ..] => (TextEncoding::Utf8, true, rest),
+            [0xFF, 0xFE, rest @ ..] => (TextEncoding::Utf16Le, true, rest),
+            [0xFE, 0xFF, rest @ ..] => (TextEncoding::Utf16Be, true, rest),
+            // JSON starts with an ASCII character, which is half zero in
+            // UTF-16.
+            [first, 0, ..] if *first != 0 => (TextEncoding::Utf16Le, false, bytes),
+            [0, second, ..] if *second != 0 => (TextEncoding::Utf16Be, false, bytes),
+            _ => (TextEncoding::Utf8, false, bytes),
+        };
+
+        let content_length = bytes.len() - bytes.iter().rev().take_while(|x| **x == 0).count();
+        // In UTF-16 the last character's zero byte isn't padding.
+        let content_length = match text {
+            TextEncoding::Utf8 => content_length,
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+                content_length.next_multiple_of(2).min(bytes.len())
+            }
+        };
+        let (content, padding) = bytes.split_at(content_length);
+
+        let decoded = match text {
+            TextEncoding::Utf8 => String::from_utf8(content.to_vec()).ok(),
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+                let units = content
+                    .chunks_exact(2)
+                    .map(|x| match text {
+                        TextEncoding::Utf16Le => u16::from_le_bytes([x[0], x[1]]),
+                        _ => u16::from_be_bytes([x[0], x[1]]),
+                    })
+                    .collect::<Vec<_>>();
+                String::from_utf16(&units).ok()
+            }
+        };
+
+        let encoding = Encoding {
+            text,
+            bom,
+            padding: padding.len(),
+        };
+
+        match decoded {
+            Some(decoded) => Ok((decoded, encoding)),
+            None => Err(SaveFileError::Decode(text)),
+        }
+    }
+
+    pub fn encode(&self, content: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        match self.text {
+            TextEncoding::Utf8 => {
+                if self.bom {
+                    bytes.extend([0xEF, 0xBB, 0xBF]);
+                }
+                bytes.extend(content.as_bytes());
+            }
+            TextEncoding::Utf16Le => {
+                if self.bom {
+                    bytes.extend([0xFF, 0xFE]);
+                }
+                bytes.extend(content.encode_utf16().flat_map(u16::to_le_bytes));
+            }
+            TextEncoding::Utf16Be => {
+                if self.bom {
+                    bytes.extend([0xFE, 0xFF]);
+                }
+                bytes.extend(content.encode_utf16().flat_map(u16::to_be_bytes));
+            }
+        }
+
+        bytes.resize(bytes.len() + self.padding, 0);
+        bytes
+    }
+}
+
+impl Display for TextEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextEncoding::Utf8 => write!(f, "UTF-8"),
+            TextEncoding::Utf16Le => write!(f, "UTF-16 LE"),
+            TextEncoding::Utf16Be => write!(f, "UTF-16 BE"),
+        }
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)?;
+        if self.bom {
+            write!(f, " with BOM")?;
+        }
+        if self.padding > 0 {
+            write!(f, ", {} NUL bytes of padding", self.padding)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn load(path: &Path) -> Result<(SaveGame, Encoding), SaveFileError> {
+    load_as(path)
+}
+
+/// Loads any JSON document, for saves that don't parse as [`SaveGame`].
+pub fn load_value(path: &Path) -> Result<(Value, Encoding), SaveFileError> {
+    load_as(path)
+}
+
+fn load_as<T: DeserializeOwned>(path: &Path) -> Result<(T, Encoding), SaveFileError> {
+    let (content, encoding) = Encoding::decode(&std::fs::read(path)?)?;
+
+    let parsed = serde_json::from_str(&content)
+        .map_err(|err| SaveFileError::Parse(Box::new(Diagnostic::new(&content, &err))))?;
+
+    Ok((parsed, encoding))
+}
+
+/// Writes `save` to `path` in `encoding`, copying whatever is already there
+/// to a timestamped `.bak` file next to it first.
+pub fn write<T: Serialize>(path: &Path, save: &T, encoding: Encoding) -> Result<(), SaveFileError> {
+    if path.is_file() {
+        std::fs::copy(path, backup_path(path))?;
+    }
+
+    let content = serde_json::to_string(save).map_err(SaveFileError::Serialize)?;
+
+    std::fs::write(path, encoding.encode(&content))?;
+
+    Ok(())
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!(
+        "{}.editor.{}.bak",
+        path.to_string_lossy(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = r#"{"SaveName":"Sävé ♪ 𝄞","TimesBooted":3}"#;
+
+    #[test]
+    fn encodings_round_trip() {
+        for text in [
+            TextEncoding::Utf8,
+            TextEncoding::Utf16Le,
+            TextEncoding::Utf16Be,
+        ] {
+            for bom in [false, true] {
+                for padding in [0, 1, 2, 3, 16] {
+                    let encoding = Encoding { text, bom, padding };
+                    let bytes = encoding.encode(CONTENT);
+
+                    let (decoded, detected) = Encoding::decode(&bytes).unwrap();
+                    assert_eq!(decoded, CONTENT, "{encoding}");
+                    assert_eq!(detected, encoding);
+                    assert_eq!(detected.encode(&decoded), bytes, "{encoding}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn byte_order_marks_are_detected() {
+        let (decoded, encoding) = Encoding::decode(b"\xEF\xBB\xBF{}").unwrap();
+        assert_eq!(decoded, "{}");
+        assert_eq!(encoding.text, TextEncoding::Utf8);
+        assert!(encoding.bom);
+
+        let (decoded, encoding) = Encoding::decode(b"\xFF\xFE{\0}\0").unwrap();
+        assert_eq!(decoded, "{}");
+        assert_eq!(encoding.text, TextEncoding::Utf16Le);
+        assert!(encoding.bom);
+
+        let (decoded, encoding) = Encoding::decode(b"\xFE\xFF\0{\0}").unwrap();
+        assert_eq!(decoded, "{}");
+        assert_eq!(encoding.text, TextEncoding::Utf16Be);
+        assert!(encoding.bom);
+    }
+
+    #[test]
+    fn utf_16_keeps_the_last_characters_zero_byte() {
+        let (decoded, encoding) = Encoding::decode(b"{\0}\0\0\0\0").unwrap();
+        assert_eq!(decoded, "{}");
+        assert_eq!(encoding.text, TextEncoding::Utf16Le);
+        assert_eq!(encoding.padding, 3);
+    }
+
+    #[test]
+    fn invalid_text_is_an_error() {
+        assert!(matches!(
+            Encoding::decode(b"{\"a\":\"\xFF\xFE\xFD\"}"),
+            Err(SaveFileError::Decode(TextEncoding::Utf8))
+        ));
+        // An unpaired surrogate.
+        assert!(matches!(
+            Encoding::decode(b"\xFF\xFE{\0\x00\xD8}\0"),
+            Err(SaveFileError::Decode(TextEncoding::Utf16Le))
+        ));
+    }
+}
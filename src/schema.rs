@@ -0,0 +1,195 @@
+//! Compares a save that doesn't parse as [`SaveGame`] with the shape the
+//! editor expects, to point out the fields that keep it from parsing.
+//!
+//! [`SaveGame`]: crate::models::SaveGame
+
+use std::fmt::Display;
+
+use serde_json::Value;
+
+use crate::{
+    diff::{Path, Segment},
+    templates,
+};
+
+/// A field that is missing or holds the wrong kind of value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    pub path: Path,
+    pub expected: &'static str,
+    /// What the save has instead, `None` if the field is missing.
+    pub found: Option<String>,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.path.0.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+
+        match &self.found {
+            Some(found) => write!(f, "expected {}, found {}", self.expected, found),
+            None => write!(f, "missing, expected {}", self.expected),
+        }
+    }
+}
+
+/// A save with one entry in every list, so each nested entry's fields are
+/// known.
+fn expected() -> Value {
+    let mut save = templates::save();
+    let mut storyline = templates::storyline(0);
+    storyline
+        .story_beat_data
+        .push(templates::story_beat(String::new()));
+
+    save.level_data.push(templates::level(String::new()));
+    save.storyline_data.push(storyline);
+    save.enemy_kill_counts_by_id.push(templates::enemy(0));
+
+    serde_json::to_value(save).unwrap_or_default()
+}
+
+/// Every field of `value` that doesn't fit what the editor expects. Fields
+/// the editor doesn't know about are left alone, they don't stop a save from
+/// parsing.
+pub fn check(value: &Value) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    check_value(&Path::default(), &expected(), value, &mut issues);
+    issues
+}
+
+fn check_value(path: &Path, expected: &Value, value: &Value, issues: &mut Vec<Issue>) {
+    match (expected, value) {
+        (Value::Bool(_), Value::Bool(_)) | (Value::String(_), Value::String(_)) => {}
+        (Value::Number(_), Value::Number(number)) if number.is_u64() => {}
+        (Value::Array(expected), Value::Array(values)) => {
+            let Some(expected) = expected.first() else {
+                return;
+            };
+
+            for (index, value) in values.iter().enumerate() {
+                check_value(&path.push(Segment::Index(index)), expected, value, issues);
+            }
+        }
+        (Value::Object(expected), Value::Object(values)) => {
+            for (name, expected) in expected {
+                let path = path.push(Segment::Field(name.clone()));
+                match values.get(name) {
+                    Some(value) => check_value(&path, expected, value, issues),
+                    None => issues.push(Issue {
+                        path,
+                        expected: kind(expected),
+                        found: None,
+                    }),
+                }
+            }
+        }
+        (expected, value) => issues.push(Issue {
+            path: path.clone(),
+            expected: kind(expected),
+            found: Some(found(value)),
+        }),
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "nothing",
+        Value::Bool(_) => "true or false",
+        Value::Number(_) => "a whole number of 0 or more",
+        Value::String(_) => "text",
+        Value::Array(_) => "a list",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn found(value: &Value) -> String {
+    match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => format!("{text:?}"),
+        value => kind(value).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn save() -> Value {
+        let mut save = templates::save();
+        save.level_data.push(templates::level("a".to_string()));
+        save.level_data.push(templates::level("b".to_string()));
+        serde_json::to_value(save).unwrap()
+    }
+
+    #[test]
+    fn accepts_the_expected_shape() {
+        assert_eq!(check(&save()), []);
+    }
+
+    #[test]
+    fn reports_fields_of_the_wrong_type() {
+        let mut save = save();
+        save["LevelDatas"][1]["DifficultyHighScoreDatas"][0]["NumClears"] = json!("many");
+        save["ShouldUnlockAllLevels"] = json!(1);
+
+        let issues = check(&save);
+        let paths = issues
+            .iter()
+            .map(|x| x.path.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "LevelDatas[1].DifficultyHighScoreDatas[0].NumClears",
+                "ShouldUnlockAllLevels"
+            ]
+        );
+        assert_eq!(
+            issues[0].path,
+            Path(vec![
+                Segment::Field("LevelDatas".to_string()),
+                Segment::Index(1),
+                Segment::Field("DifficultyHighScoreDatas".to_string()),
+                Segment::Index(0),
+                Segment::Field("NumClears".to_string()),
+            ])
+        );
+        assert_eq!(issues[0].expected, "a whole number of 0 or more");
+        assert_eq!(issues[0].found.as_deref(), Some("\"many\""));
+        assert_eq!(issues[1].found.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn reports_missing_fields() {
+        let mut save = save();
+        save["LevelDatas"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("LevelId");
+
+        assert_eq!(
+            check(&save),
+            [Issue {
+                path: Path(vec![
+                    Segment::Field("LevelDatas".to_string()),
+                    Segment::Index(0),
+                    Segment::Field("LevelId".to_string()),
+                ]),
+                expected: "text",
+                found: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_fields_alone() {
+        let mut save = save();
+        save["SomethingNew"] = json!([1, "two"]);
+
+        assert_eq!(check(&save), []);
+    }
+}
@@ -0,0 +1,149 @@
+//! Blank entries used when adding new items to a save.
+
+use serde_json::Map;
+
+use crate::{
+    catalog,
+    models::{DifficultyData, EnemyKillCount, LevelData, SaveGame, StoryBeatData, StorylineData},
+    versions,
+};
+
+/// A save that was never played, with no entries in any of its lists.
+pub fn save() -> SaveGame {
+    SaveGame {
+        save_name: String::new(),
+        game_data_version: versions::CURRENT.game_data,
+        save_data_version: versions::CURRENT.save_data,
+        times_booted: 0,
+        save_id: 0,
+        player_id: String::new(),
+        selected_language: String::new(),
+        framerate_limit: 0,
+        level_data: Vec::new(),
+        storyline_data: Vec::new(),
+        active_cosmetic_pin: String::new(),
+        active_gameplay_pin: String::new(),
+        should_display_dialogue_debug: false,
+        should_unlock_all_levels: false,
+        has_input_dragon_dance: false,
+        selected_story_difficulty: 0,
+        selected_arcade_difficulty: 0,
+        selected_track_sorting_order: 0,
+        selected_custom_music_sorting_order: 0,
+        is_remix_mode_active: false,
+        should_play_all_story_content_in_order: false,
+        enemy_kill_counts_by_id: Vec::new(),
+        total_rhythm_rifts_cleared: 0,
+        has_seen_splash_screens: false,
+        has_opened_story_mode: false,
+        has_agreed_to_no_streaming: false,
+        total_diamonds: 0,
+        total_vibe_power_uses: 0,
+        max_enemies_killed_while_vibing: 0,
+        bb_total_attacks: 0,
+        bb_total_dodges: 0,
+        bb_total_blocked_hits: 0,
+        extra: Map::new(),
+    }
+}
+
+/// A level that was never played, with an empty entry for every known
+/// difficulty in both normal and remix mode.
+pub fn level(level_id: String) -> LevelData {
+    LevelData {
+        level_id,
+        stage_type: 0,
+        was_completed_in_story_mode: false,
+        was_attempted_in_story_mode: false,
+        was_skipped_in_story_mode: false,
+        awarded_diamonds: 0,
+        awarded_diamonds_remix: 0,
+        difficulty_data: catalog::DIFFICULTIES
+            .iter()
+            .copied()
+            .map(difficulty)
+            .collect(),
+        remix_difficulty_data: catalog::DIFFICULTIES
+            .iter()
+            .copied()
+            .map(difficulty)
+            .collect(),
+        extra: Map::new(),
+    }
+}
+
+pub fn difficulty(difficulty: u64) -> DifficultyData {
+    DifficultyData {
+        difficulty,
+        high_score: 0,
+        letter_grade: String::new(),
+        max_combo_count: 0,
+        num_attempts: 0,
+        num_clears: 0,
+        num_retries: 0,
+        num_game_overs: 0,
+        has_all_perfects: false,
+        has_full_combo_rhythm_rift: false,
+        extra: Map::new(),
+    }
+}
+
+pub fn storyline(storyline_characters: u64) -> StorylineData {
+    StorylineData {
+        storyline_characters,
+        has_unlocked_storyline: false,
+        has_completed_storyline: false,
+        story_beat_data: Vec::new(),
+        extra: Map::new(),
+    }
+}
+
+pub fn story_beat(level_id: String) -> StoryBeatData {
+    StoryBeatData {
+        level_id,
+        times_played: 0,
+        extra: Map::new(),
+    }
+}
+
+pub fn enemy(enemy_id: u64) -> EnemyKillCount {
+    EnemyKillCount {
+        enemy_id,
+        number_of_kills: 0,
+        number_of_deaths: 0,
+        extra: Map::new(),
+    }
+}
+
+/// One more than the highest enemy ID in the save, 0 if it has none.
+pub fn next_enemy_id(save: &SaveGame) -> u64 {
+    save.enemy_kill_counts_by_id
+        .iter()
+        .map(|x| x.enemy_id + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// One more than the highest storyline character value in the save, 0 if it
+/// has none.
+pub fn next_storyline_characters(save: &SaveGame) -> u64 {
+    save.storyline_data
+        .iter()
+        .map(|x| x.storyline_characters + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// An ID that isn't taken by any level yet, based on `level_id`.
+pub fn unique_level_id(save: &SaveGame, level_id: &str) -> String {
+    let taken = |id: &str| save.level_data.iter().any(|level| level.level_id == id);
+
+    if !taken(level_id) {
+        return level_id.to_string();
+    }
+
+    (2..)
+        .map(|n| format!("{level_id}_{n}"))
+        .find(|id| !taken(id))
+        .expect("ran out of level IDs")
+}
@@ -0,0 +1,331 @@
+//! Save format versions the editor knows, and the steps to move a save from
+//! one to another.
+//!
+//! Migrations work on the plain JSON document instead of [`SaveGame`], since
+//! any version other than the current one can have a different shape.
+//!
+//! [`SaveGame`]: crate::models::SaveGame
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// The pair of numbers every save is tagged with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Version {
+    pub game_data: u64,
+    pub save_data: u64,
+}
+
+/// Moves a save between two neighbouring known versions. Both only reshape
+/// the document, the version fields are set afterwards.
+pub struct Migration {
+    pub from: Version,
+    pub to: Version,
+    pub upgrade: fn(&mut Value),
+    pub downgrade: fn(&mut Value),
+}
+
+/// Known versions, oldest first, and the migrations between each pair of
+/// neighbouring ones.
+pub struct Registry {
+    pub known: &'static [Version],
+    pub migrations: &'static [Migration],
+}
+
+/// The version the models and templates describe.
+pub const CURRENT: Version = Version {
+    game_data: 0,
+    save_data: 0,
+};
+
+/// Versions the editor fully understands, oldest first, ending with
+/// [`CURRENT`].
+pub const KNOWN: &[Version] = &[CURRENT];
+
+/// Steps between each pair of neighbouring [`KNOWN`] versions.
+pub const MIGRATIONS: &[Migration] = &[];
+
+pub const REGISTRY: Registry = Registry {
+    known: KNOWN,
+    migrations: MIGRATIONS,
+};
+
+#[derive(Debug, Error)]
+pub enum VersionError {
+    #[error("The save has no GameDataVersion and SaveDataVersion")]
+    Missing,
+    #[error("Version {0} isn't one the editor knows")]
+    Unknown(Version),
+    #[error("Nothing migrates a save from version {0} to {1}")]
+    NoMigration(Version, Version),
+    #[error("\"{0}\" isn't a version, expected GAME/SAVE such as 1/1")]
+    Invalid(String),
+}
+
+impl Version {
+    pub fn is_known(&self) -> bool {
+        REGISTRY.known.contains(self)
+    }
+
+    /// Versions a save of this version can be migrated to.
+    pub fn targets(&self) -> Vec<Version> {
+        REGISTRY.targets(*self)
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.game_data, self.save_data)
+    }
+}
+
+impl FromStr for Version {
+    type Err = VersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || VersionError::Invalid(s.to_string());
+        let (game_data, save_data) = s.split_once('/').ok_or_else(invalid)?;
+
+        Ok(Version {
+            game_data: game_data.trim().parse().map_err(|_| invalid())?,
+            save_data: save_data.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// The version a save document is tagged with.
+pub fn version_of(save: &Value) -> Option<Version> {
+    Some(Version {
+        game_data: save.get("GameDataVersion")?.as_u64()?,
+        save_data: save.get("SaveDataVersion")?.as_u64()?,
+    })
+}
+
+fn set_version(save: &mut Value, version: Version) {
+    if let Some(save) = save.as_object_mut() {
+        save.insert("GameDataVersion".to_string(), version.game_data.into());
+        save.insert("SaveDataVersion".to_string(), version.save_data.into());
+    }
+}
+
+impl Registry {
+    /// Versions a save of version `from` can be migrated to.
+    pub fn targets(&self, from: Version) -> Vec<Version> {
+        self.known
+            .iter()
+            .copied()
+            .filter(|target| *target != from)
+            .filter(|target| self.path(from, *target).is_ok())
+            .collect()
+    }
+
+    /// Upgrades or downgrades `save` to `target` one known version at a
+    /// time. Returns the number of steps taken.
+    pub fn migrate(&self, save: &mut Value, target: Version) -> Result<usize, VersionError> {
+        let current = version_of(save).ok_or(VersionError::Missing)?;
+        let steps = self.path(current, target)?;
+
+        for (migration, downgrade) in &steps {
+            if *downgrade {
+                (migration.downgrade)(save);
+                set_version(save, migration.from);
+            } else {
+                (migration.upgrade)(save);
+                set_version(save, migration.to);
+            }
+        }
+
+        Ok(steps.len())
+    }
+
+    fn position(&self, version: Version) -> Result<usize, VersionError> {
+        self.known
+            .iter()
+            .position(|x| *x == version)
+            .ok_or(VersionError::Unknown(version))
+    }
+
+    /// The step between two neighbouring known versions, from the older one.
+    fn step(&self, index: usize) -> Option<&'static Migration> {
+        let (from, to) = (self.known[index], self.known[index + 1]);
+        self.migrations
+            .iter()
+            .find(|x| x.from == from && x.to == to)
+    }
+
+    /// Every step from `from` to `to`, with whether it's run as a downgrade.
+    fn path(
+        &self,
+        from: Version,
+        to: Version,
+    ) -> Result<Vec<(&'static Migration, bool)>, VersionError> {
+        let (start, end) = (self.position(from)?, self.position(to)?);
+        let missing = || VersionError::NoMigration(from, to);
+
+        if start <= end {
+            (start..end)
+                .map(|index| Ok((self.step(index).ok_or_else(missing)?, false)))
+                .collect()
+        } else {
+            (end..start)
+                .rev()
+                .map(|index| Ok((self.step(index).ok_or_else(missing)?, true)))
+                .collect()
+        }
+    }
+}
+
+/// Upgrades or downgrades `save` to `target` through the [`REGISTRY`].
+/// Returns the number of steps taken.
+pub fn migrate(save: &mut Value, target: Version) -> Result<usize, VersionError> {
+    REGISTRY.migrate(save, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::templates;
+
+    const OLDEST: Version = Version {
+        game_data: 1,
+        save_data: 1,
+    };
+    const MIDDLE: Version = Version {
+        game_data: 1,
+        save_data: 2,
+    };
+    const NEWEST: Version = Version {
+        game_data: 2,
+        save_data: 2,
+    };
+
+    /// Renames `Diamonds` to `TotalDiamonds`, then adds a list.
+    const TESTED: Registry = Registry {
+        known: &[OLDEST, MIDDLE, NEWEST],
+        migrations: &[
+            Migration {
+                from: MIDDLE,
+                to: NEWEST,
+                upgrade: |save| {
+                    save["Pins"] = json!([]);
+                },
+                downgrade: |save| {
+                    save.as_object_mut().unwrap().remove("Pins");
+                },
+            },
+            Migration {
+                from: OLDEST,
+                to: MIDDLE,
+                upgrade: |save| {
+                    let save = save.as_object_mut().unwrap();
+                    let diamonds = save.remove("Diamonds").unwrap();
+                    save.insert("TotalDiamonds".to_string(), diamonds);
+                },
+                downgrade: |save| {
+                    let save = save.as_object_mut().unwrap();
+                    let diamonds = save.remove("TotalDiamonds").unwrap();
+                    save.insert("Diamonds".to_string(), diamonds);
+                },
+            },
+        ],
+    };
+
+    fn old_save() -> Value {
+        json!({
+            "SaveName": "a",
+            "GameDataVersion": 1,
+            "SaveDataVersion": 1,
+            "Diamonds": 12,
+        })
+    }
+
+    #[test]
+    fn round_trips_up_then_down() {
+        let mut save = old_save();
+
+        assert_eq!(TESTED.migrate(&mut save, NEWEST).unwrap(), 2);
+        assert_eq!(version_of(&save), Some(NEWEST));
+        assert_eq!(save["TotalDiamonds"], 12);
+        assert_eq!(save["Pins"], json!([]));
+        assert!(save.get("Diamonds").is_none());
+
+        assert_eq!(TESTED.migrate(&mut save, OLDEST).unwrap(), 2);
+        assert_eq!(save, old_save());
+    }
+
+    #[test]
+    fn stops_at_versions_in_between() {
+        let mut save = old_save();
+
+        assert_eq!(TESTED.migrate(&mut save, MIDDLE).unwrap(), 1);
+        assert_eq!(version_of(&save), Some(MIDDLE));
+        assert!(save.get("Pins").is_none());
+        assert_eq!(TESTED.targets(MIDDLE), [OLDEST, NEWEST]);
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let mut save = old_save();
+        let unknown = Version {
+            game_data: 9,
+            save_data: 9,
+        };
+
+        assert!(matches!(
+            TESTED.migrate(&mut save, unknown),
+            Err(VersionError::Unknown(x)) if x == unknown
+        ));
+        assert!(matches!(
+            TESTED.migrate(&mut json!({}), NEWEST),
+            Err(VersionError::Missing)
+        ));
+        assert_eq!(save, old_save());
+        assert_eq!(TESTED.targets(unknown), []);
+    }
+
+    #[test]
+    fn every_migration_round_trips_a_template() {
+        for (index, pair) in REGISTRY.known.windows(2).enumerate() {
+            assert!(
+                REGISTRY.step(index).is_some(),
+                "nothing migrates {}",
+                pair[0]
+            );
+        }
+
+        let mut save = serde_json::to_value(templates::save()).unwrap();
+        let original = save.clone();
+        for target in REGISTRY.known.iter().rev().chain(REGISTRY.known) {
+            REGISTRY.migrate(&mut save, *target).unwrap();
+        }
+        assert_eq!(save, original);
+    }
+
+    #[test]
+    fn templates_are_current() {
+        let save = serde_json::to_value(templates::save()).unwrap();
+
+        assert_eq!(version_of(&save), Some(CURRENT));
+        assert!(CURRENT.is_known());
+        assert_eq!(KNOWN.last(), Some(&CURRENT));
+    }
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!("1/2".parse::<Version>().unwrap(), MIDDLE);
+        assert_eq!(" 2 / 2 ".parse::<Version>().unwrap(), NEWEST);
+        assert!(matches!(
+            "1".parse::<Version>(),
+            Err(VersionError::Invalid(_))
+        ));
+        assert!(matches!(
+            "1/x".parse::<Version>(),
+            Err(VersionError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+use iced::{
+    Element, Length,
+    alignment::Vertical,
+    color,
+    widget::{button, column, row, text},
+};
+use serde_json::Value;
+
+use crate::{
+    Message,
+    diff::{Change, Segment},
+    models::SaveGame,
+};
+
+use super::{EditorMessage, RevertTarget};
+
+const MAX_VALUE_LENGTH: usize = 40;
+
+/// Sections the changes are grouped under, by top level field.
+const SECTIONS: &[(&str, Option<&str>)] = &[
+    ("General", None),
+    ("Levels", Some("LevelDatas")),
+    ("Storylines", Some("StorylineDatas")),
+    ("Enemy Kill Counts", Some("EnemyKillCountsById")),
+];
+
+fn top_level_field(change: &Change) -> Option<&str> {
+    match change.path.0.first() {
+        Some(Segment::Field(name)) => Some(name),
+        _ => None,
+    }
+}
+
+fn in_section(change: &Change, field: Option<&str>) -> bool {
+    let top = top_level_field(change);
+    match field {
+        Some(field) => top == Some(field),
+        None => !SECTIONS
+            .iter()
+            .any(|(_, field)| field.is_some() && *field == top),
+    }
+}
+
+/// Top level fields kept as they are, with no row in the editor.
+const WITHOUT_ROW: &[&str] = &[
+    "SelectedTrackSortingOrder",
+    "SelectedCustomMusicSortingOrder",
+    "HasSeenSplashScreens",
+    "HasOpenedStoryMode",
+    "HasAgreedToNoStreaming",
+];
+
+/// Search query that brings up the top level field `name` in the editor, if
+/// it has a row there.
+pub fn field_query(name: &str) -> Option<String> {
+    match name {
+        "StorylineDatas" => Some("Storylines".to_string()),
+        "EnemyKillCountsById" => Some("Enemy Kill Counts".to_string()),
+        name if WITHOUT_ROW.contains(&name) => None,
+        name => Some(name.to_string()),
+    }
+}
+
+/// Whether the editor has somewhere to show the change. Fields it doesn't
+/// know about are only in the JSON tab.
+fn can_go_to(change: &Change, data: &SaveGame) -> bool {
+    change.new.is_some()
+        && match change.path.0.first() {
+            Some(Segment::Field(name)) if data.extra.contains_key(name) => false,
+            Some(Segment::Field(name)) => name == "LevelDatas" || field_query(name).is_some(),
+            _ => false,
+        }
+}
+
+fn format_value(value: Option<&Value>) -> String {
+    let Some(value) = value else {
+        return "(none)".to_string();
+    };
+
+    let formatted = value.to_string();
+    if formatted.chars().count() <= MAX_VALUE_LENGTH {
+        return formatted;
+    }
+
+    let mut shortened = formatted
+        .chars()
+        .take(MAX_VALUE_LENGTH - 3)
+        .collect::<String>();
+    shortened.push_str("...");
+    shortened
+}
+
+/// Every change between the loaded and the edited save, grouped by section.
+pub fn view<'a>(changes: &'a [Change], data: &SaveGame) -> Element<'a, Message> {
+    if changes.is_empty() {
+        return text("No changes").color(color!(0x999999)).into();
+    }
+
+    column(SECTIONS.iter().filter_map(|(title, field)| {
+        let entries = changes
+            .iter()
+            .filter(|change| in_section(change, *field))
+            .map(|change| change_entry(change, data))
+            .collect::<Vec<_>>();
+
+        (!entries.is_empty()).then(|| {
+            column![
+                text(format!("{title} ({})", entries.len())).size(18.0),
+                column(entries).spacing(8.0)
+            ]
+            .spacing(4.0)
+            .into()
+        })
+    }))
+    .spacing(12.0)
+    .into()
+}
+
+fn change_entry<'a>(change: &'a Change, data: &SaveGame) -> Element<'a, Message> {
+    column![
+        text(change.path.to_string()).size(14.0),
+        row![
+            text(format_value(change.old.as_ref()))
+                .size(12.0)
+                .color(color!(0x999999)),
+            text("->").size(12.0),
+            text(format_value(change.new.as_ref())).size(12.0),
+        ]
+        .spacing(4.0),
+        row![
+            button(text("Go to").size(12.0))
+                .style(button::secondary)
+                .padding([2.0, 6.0])
+                .on_press_maybe(
+                    can_go_to(change, data)
+                        .then(|| EditorMessage::GoToChange(change.path.clone()).into())
+                ),
+            button(text("Revert").size(12.0))
+                .style(button::secondary)
+                .padding([2.0, 6.0])
+                .on_press(EditorMessage::Revert(RevertTarget::Change(change.path.clone())).into()),
+        ]
+        .align_y(Vertical::Center)
+        .spacing(4.0)
+    ]
+    .width(Length::Fill)
+    .spacing(2.0)
+    .into()
+}
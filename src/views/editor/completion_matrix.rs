@@ -0,0 +1,200 @@
+use iced::{
+    Background, Border, Color, Element, Length, color,
+    widget::{button, column, container, row, text},
+};
+
+use crate::{
+    Message, catalog, diff,
+    models::{DifficultyData, LevelData},
+};
+
+use super::{
+    DifficultySlot,
+    filter::EditorFilter,
+    level_table::{LevelTableMessage, LevelTableState},
+};
+
+const LEVEL_WIDTH: f32 = 220.0;
+const CELL_WIDTH: f32 = 64.0;
+
+const ALL_PERFECT: Color = color!(0xFFD54F);
+const FULL_COMBO: Color = color!(0x81C784);
+const MISSING: Color = color!(0xE0E0E0);
+
+/// Levels down the side, every difficulty across the top with normal and
+/// remix next to each other.
+pub fn view<'a>(
+    state: &LevelTableState,
+    filter: &EditorFilter,
+    levels: &'a [LevelData],
+    original: &'a [LevelData],
+) -> Element<'a, Message> {
+    let mut difficulties = levels
+        .iter()
+        .flat_map(|level| {
+            level
+                .difficulty_data
+                .iter()
+                .chain(&level.remix_difficulty_data)
+        })
+        .map(|x| x.difficulty)
+        .collect::<Vec<_>>();
+    difficulties.sort();
+    difficulties.dedup();
+
+    let header = row![container(text("Level").size(14.0)).width(Length::Fixed(LEVEL_WIDTH))]
+        .extend(difficulties.iter().flat_map(|difficulty| {
+            let name = catalog::difficulty_name(*difficulty);
+            [header_cell(name.clone()), header_cell(format!("{name} R"))]
+        }))
+        .spacing(2.0);
+
+    let rows = levels
+        .iter()
+        .enumerate()
+        .filter(|(_, level)| filter.shows_level(level, diff::match_level(original, level)))
+        .map(|(index, level)| {
+            row![
+                container(text(level.level_id.clone()).size(14.0))
+                    .width(Length::Fixed(LEVEL_WIDTH))
+            ]
+            .extend(difficulties.iter().flat_map(|difficulty| {
+                [false, true].map(|remix| {
+                    let data = if remix {
+                        &level.remix_difficulty_data
+                    } else {
+                        &level.difficulty_data
+                    };
+
+                    let entry = data
+                        .iter()
+                        .enumerate()
+                        .find(|(_, x)| x.difficulty == *difficulty)
+                        .map(|(position, x)| {
+                            let slot = DifficultySlot {
+                                remix,
+                                index: position,
+                            };
+                            (slot, x)
+                        });
+
+                    let selected = entry.is_some_and(|(slot, _)| {
+                        state.selected == Some(index) && state.focused == Some(slot)
+                    });
+
+                    cell(index, entry, selected)
+                })
+            }))
+            .spacing(2.0)
+            .into()
+        });
+
+    column![header, column(rows).spacing(2.0), legend()]
+        .spacing(4.0)
+        .into()
+}
+
+fn header_cell<'a>(label: String) -> Element<'a, Message> {
+    container(text(label).size(12.0))
+        .width(Length::Fixed(CELL_WIDTH))
+        .into()
+}
+
+fn cell<'a>(
+    level_index: usize,
+    entry: Option<(DifficultySlot, &DifficultyData)>,
+    selected: bool,
+) -> Element<'a, Message> {
+    let (label, background) = match entry {
+        None => ("-".to_string(), MISSING),
+        Some((_, data)) => (label(data), status_color(data)),
+    };
+
+    button(text(label).size(12.0))
+        .width(Length::Fixed(CELL_WIDTH))
+        .padding([2.0, 4.0])
+        .style(move |_, status| button::Style {
+            background: Some(Background::Color(match status {
+                button::Status::Hovered => background.scale_alpha(0.7),
+                _ => background,
+            })),
+            text_color: color!(0x222222),
+            border: Border {
+                color: color!(0x222222),
+                width: if selected { 2.0 } else { 0.0 },
+                radius: 4.0.into(),
+            },
+            ..Default::default()
+        })
+        .on_press_maybe(entry.map(|(slot, _)| LevelTableMessage::Focus(level_index, slot).into()))
+        .into()
+}
+
+fn label(data: &DifficultyData) -> String {
+    let grade = if data.letter_grade.is_empty() {
+        "-"
+    } else {
+        data.letter_grade.as_str()
+    };
+
+    if data.has_all_perfects {
+        format!("{grade} AP")
+    } else if data.has_full_combo_rhythm_rift {
+        format!("{grade} FC")
+    } else {
+        grade.to_string()
+    }
+}
+
+fn status_color(data: &DifficultyData) -> Color {
+    if data.has_all_perfects {
+        return ALL_PERFECT;
+    }
+
+    if data.has_full_combo_rhythm_rift {
+        return FULL_COMBO;
+    }
+
+    grade_color(&data.letter_grade)
+}
+
+fn grade_color(grade: &str) -> Color {
+    match grade.trim().chars().next().map(|x| x.to_ascii_uppercase()) {
+        Some('S') => color!(0xB39DDB),
+        Some('A') => color!(0x90CAF9),
+        Some('B') => color!(0x80CBC4),
+        Some('C') => color!(0xFFF59D),
+        Some('D') => color!(0xFFCC80),
+        Some('F') => color!(0xEF9A9A),
+        _ => color!(0xF5F5F5),
+    }
+}
+
+fn legend<'a>() -> Element<'a, Message> {
+    let swatch = |label: &'a str, background: Color| {
+        container(text(label).size(12.0))
+            .padding([2.0, 6.0])
+            .style(move |_| container::Style {
+                background: Some(Background::Color(background)),
+                border: Border {
+                    radius: 4.0.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+    };
+
+    row![
+        swatch("All Perfect", ALL_PERFECT),
+        swatch("Full Combo", FULL_COMBO),
+        swatch("S", grade_color("S")),
+        swatch("A", grade_color("A")),
+        swatch("B", grade_color("B")),
+        swatch("C", grade_color("C")),
+        swatch("D", grade_color("D")),
+        swatch("F", grade_color("F")),
+        swatch("No entry", MISSING),
+    ]
+    .spacing(4.0)
+    .into()
+}
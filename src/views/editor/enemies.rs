@@ -0,0 +1,50 @@
+use iced::{
+    Element,
+    alignment::Vertical,
+    widget::{button, row},
+};
+
+use crate::{
+    Message,
+    catalog::PickerKind,
+    field::{Entry, FieldAddress},
+    models::EnemyKillCount,
+};
+
+use super::{EditorMessage, num_field, pick_num_field};
+
+pub fn enemy_edit<'a>(
+    index: usize,
+    enemy: &'a EnemyKillCount,
+    original: Option<&'a EnemyKillCount>,
+) -> Element<'a, Message> {
+    let original = original.unwrap_or(enemy);
+
+    row![
+        pick_num_field(
+            "Enemy ID".into(),
+            enemy.enemy_id,
+            original.enemy_id,
+            PickerKind::EnemyId,
+            FieldAddress::new(Entry::Enemy(index), "EnemyId")
+        ),
+        num_field(
+            "Kills".into(),
+            enemy.number_of_kills,
+            original.number_of_kills,
+            FieldAddress::new(Entry::Enemy(index), "NumberOfKills")
+        ),
+        num_field(
+            "Deaths".into(),
+            enemy.number_of_deaths,
+            original.number_of_deaths,
+            FieldAddress::new(Entry::Enemy(index), "NumberOfDeaths")
+        ),
+        button("Remove")
+            .style(button::danger)
+            .on_press(EditorMessage::RemoveEnemy(index).into())
+    ]
+    .align_y(Vertical::Center)
+    .spacing(16.0)
+    .into()
+}
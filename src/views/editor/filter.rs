@@ -0,0 +1,147 @@
+use std::fmt::Display;
+
+use iced::{
+    Element,
+    alignment::Vertical,
+    widget::{checkbox, column, pick_list, row, text_input},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{Message, catalog, models::LevelData};
+
+use super::EditorMessage;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EditorFilter {
+    query: String,
+    only_changed: bool,
+    only_not_full_combo: bool,
+    stage_type: StageTypeFilter,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum FilterMessage {
+    EditQuery(String),
+    ToggleOnlyChanged(bool),
+    ToggleOnlyNotFullCombo(bool),
+    SelectStageType(StageTypeFilter),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum StageTypeFilter {
+    #[default]
+    Any,
+    Is(u64),
+}
+
+impl Display for StageTypeFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StageTypeFilter::Any => write!(f, "Any stage type"),
+            StageTypeFilter::Is(stage_type) => write!(f, "Stage type {stage_type}"),
+        }
+    }
+}
+
+impl EditorFilter {
+    pub fn update(&mut self, message: FilterMessage) {
+        match message {
+            FilterMessage::EditQuery(query) => self.query = query,
+            FilterMessage::ToggleOnlyChanged(value) => self.only_changed = value,
+            FilterMessage::ToggleOnlyNotFullCombo(value) => self.only_not_full_combo = value,
+            FilterMessage::SelectStageType(stage_type) => self.stage_type = stage_type,
+        }
+    }
+
+    fn query(&self) -> Option<String> {
+        let query = self.query.trim();
+        (!query.is_empty()).then(|| query.to_lowercase())
+    }
+
+    /// Whether a top level field is shown. The level-only filters don't apply
+    /// to these. Spaces are ignored so the save's own field names match too.
+    pub fn shows_field(&self, name: &str, changed: bool) -> bool {
+        if self.only_changed && !changed {
+            return false;
+        }
+
+        self.query().is_none_or(|query| {
+            name.to_lowercase()
+                .replace(' ', "")
+                .contains(&query.replace(' ', ""))
+        })
+    }
+
+    /// Whether a level is shown. The search matches its ID or its title.
+    pub fn shows_level(&self, level: &LevelData, original: Option<&LevelData>) -> bool {
+        if self.only_changed && original == Some(level) {
+            return false;
+        }
+
+        // A level counts as full combo once every difficulty it has, remixes
+        // included, was full comboed. One without any wasn't played yet.
+        let mut difficulties = level
+            .difficulty_data
+            .iter()
+            .chain(&level.remix_difficulty_data)
+            .peekable();
+        if self.only_not_full_combo
+            && difficulties.peek().is_some()
+            && difficulties.all(|difficulty| difficulty.has_full_combo_rhythm_rift)
+        {
+            return false;
+        }
+
+        if matches!(self.stage_type, StageTypeFilter::Is(x) if x != level.stage_type) {
+            return false;
+        }
+
+        self.query().is_none_or(|query| {
+            level.level_id.to_lowercase().contains(&query)
+                || catalog::level_title(&level.level_id)
+                    .to_lowercase()
+                    .contains(&query)
+        })
+    }
+}
+
+pub fn view<'a>(filter: &'a EditorFilter, levels: &[LevelData]) -> Element<'a, Message> {
+    let mut stage_types = levels
+        .iter()
+        .map(|level| level.stage_type)
+        .collect::<Vec<_>>();
+    stage_types.sort();
+    stage_types.dedup();
+
+    let options = std::iter::once(StageTypeFilter::Any)
+        .chain(stage_types.into_iter().map(StageTypeFilter::Is))
+        .collect::<Vec<_>>();
+
+    column![
+        text_input(
+            "Search fields and levels by name, ID or title",
+            &filter.query
+        )
+        .on_input(|x| FilterMessage::EditQuery(x).into()),
+        row![
+            checkbox("Only changed", filter.only_changed)
+                .on_toggle(|x| FilterMessage::ToggleOnlyChanged(x).into()),
+            checkbox("Only not full combo", filter.only_not_full_combo)
+                .on_toggle(|x| FilterMessage::ToggleOnlyNotFullCombo(x).into()),
+            pick_list(options, Some(filter.stage_type), |x| {
+                FilterMessage::SelectStageType(x).into()
+            }),
+        ]
+        .align_y(Vertical::Center)
+        .spacing(12.0)
+    ]
+    .spacing(4.0)
+    .into()
+}
+
+impl Into<Message> for FilterMessage {
+    fn into(self) -> Message {
+        Message::Editor(EditorMessage::Filter(self))
+    }
+}
@@ -0,0 +1,286 @@
+use std::cmp::Ordering;
+
+use iced::{
+    Background, Element, Length, color,
+    widget::{button, column, container, row, text},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{Message, catalog, diff, models::LevelData};
+
+use super::{DifficultySlot, EditorMessage, filter::EditorFilter};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LevelTableState {
+    pub layout: LevelLayout,
+    sort: LevelColumn,
+    descending: bool,
+    /// Index into `level_data` of the level open in the side panel.
+    pub selected: Option<usize>,
+    /// Difficulty entry the side panel is narrowed down to.
+    pub focused: Option<DifficultySlot>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LevelLayout {
+    #[default]
+    Table,
+    Matrix,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LevelColumn {
+    /// Order the levels appear in the save.
+    #[default]
+    Index,
+    Id,
+    StageType,
+    Story,
+    Diamonds,
+    Grade(u64),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum LevelTableMessage {
+    SetLayout(LevelLayout),
+    SortBy(LevelColumn),
+    Select(usize),
+    Focus(usize, DifficultySlot),
+    CloseDetails,
+}
+
+impl LevelTableState {
+    pub fn update(&mut self, message: LevelTableMessage) {
+        match message {
+            LevelTableMessage::SortBy(column) => {
+                if self.sort == column {
+                    self.descending = !self.descending;
+                } else {
+                    self.sort = column;
+                    self.descending = false;
+                }
+            }
+            LevelTableMessage::SetLayout(layout) => self.layout = layout,
+            LevelTableMessage::Select(index) => self.select(index),
+            LevelTableMessage::Focus(index, slot) => {
+                self.selected = Some(index);
+                self.focused = Some(slot);
+            }
+            LevelTableMessage::CloseDetails => {
+                self.selected = None;
+                self.focused = None;
+            }
+        }
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.selected = Some(index);
+        self.focused = None;
+    }
+
+    /// Keeps the selection pointing at the same level after the one at
+    /// `index` is removed.
+    pub fn level_removed(&mut self, index: usize) {
+        match self.selected {
+            Some(selected) if selected == index => {
+                self.selected = None;
+                self.focused = None;
+            }
+            Some(selected) if selected > index => self.selected = Some(selected - 1),
+            _ => {}
+        }
+    }
+
+    /// Drops a selection that no longer points at a level.
+    pub fn clamp(&mut self, len: usize) {
+        if self.selected.is_some_and(|selected| selected >= len) {
+            self.selected = None;
+            self.focused = None;
+        }
+    }
+
+    fn compare(&self, a: &LevelData, b: &LevelData) -> Ordering {
+        let ordering = match self.sort {
+            LevelColumn::Index => Ordering::Equal,
+            LevelColumn::Id => a.level_id.cmp(&b.level_id),
+            LevelColumn::StageType => a.stage_type.cmp(&b.stage_type),
+            LevelColumn::Story => story_rank(a).cmp(&story_rank(b)),
+            LevelColumn::Diamonds => total_diamonds(a).cmp(&total_diamonds(b)),
+            LevelColumn::Grade(difficulty) => {
+                grade_rank(a, difficulty).cmp(&grade_rank(b, difficulty))
+            }
+        };
+
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+const ID_WIDTH: f32 = 220.0;
+const STAGE_TYPE_WIDTH: f32 = 70.0;
+const STORY_WIDTH: f32 = 90.0;
+const DIAMONDS_WIDTH: f32 = 80.0;
+const GRADE_WIDTH: f32 = 80.0;
+
+pub fn view<'a>(
+    state: &LevelTableState,
+    filter: &EditorFilter,
+    levels: &'a [LevelData],
+    original: &'a [LevelData],
+) -> Element<'a, Message> {
+    let mut difficulties = levels
+        .iter()
+        .flat_map(|level| level.difficulty_data.iter().map(|x| x.difficulty))
+        .collect::<Vec<_>>();
+    difficulties.sort();
+    difficulties.dedup();
+
+    let mut rows = levels
+        .iter()
+        .enumerate()
+        .filter(|(_, level)| filter.shows_level(level, diff::match_level(original, level)))
+        .collect::<Vec<_>>();
+    // Stable, so ties stay in save order.
+    rows.sort_by(|(_, a), (_, b)| state.compare(a, b));
+
+    let header = row![
+        header_cell(state, "ID", LevelColumn::Id, ID_WIDTH),
+        header_cell(state, "Stage", LevelColumn::StageType, STAGE_TYPE_WIDTH),
+        header_cell(state, "Story", LevelColumn::Story, STORY_WIDTH),
+        header_cell(state, "Diamonds", LevelColumn::Diamonds, DIAMONDS_WIDTH),
+    ]
+    .extend(difficulties.iter().map(|difficulty| {
+        header_cell(
+            state,
+            &catalog::difficulty_name(*difficulty),
+            LevelColumn::Grade(*difficulty),
+            GRADE_WIDTH,
+        )
+    }))
+    .spacing(2.0);
+
+    let body = rows.into_iter().map(|(index, level)| {
+        let changed = diff::match_level(original, level) != Some(level);
+
+        let cells = row![
+            cell(level.level_id.clone(), ID_WIDTH),
+            cell(level.stage_type.to_string(), STAGE_TYPE_WIDTH),
+            cell(story_label(level).to_string(), STORY_WIDTH),
+            cell(
+                format!(
+                    "{} / {}",
+                    level.awarded_diamonds, level.awarded_diamonds_remix
+                ),
+                DIAMONDS_WIDTH
+            ),
+        ]
+        .extend(difficulties.iter().map(|difficulty| {
+            let grade = level
+                .difficulty_data
+                .iter()
+                .find(|x| x.difficulty == *difficulty)
+                .map(|x| x.letter_grade.clone())
+                .unwrap_or_default();
+            cell(grade, GRADE_WIDTH)
+        }))
+        .spacing(2.0);
+
+        let selected = state.selected == Some(index);
+
+        button(cells)
+            .padding([2.0, 0.0])
+            .width(Length::Fill)
+            .style(move |theme, status| {
+                let mut style = if selected {
+                    button::primary(theme, status)
+                } else {
+                    button::text(theme, status)
+                };
+                if changed && !selected {
+                    style.background = Some(Background::Color(color!(0xFFF4CC)));
+                }
+                style
+            })
+            .on_press(LevelTableMessage::Select(index).into())
+            .into()
+    });
+
+    column![header, column(body).spacing(1.0)]
+        .spacing(4.0)
+        .into()
+}
+
+fn header_cell<'a>(
+    state: &LevelTableState,
+    label: &str,
+    column: LevelColumn,
+    width: f32,
+) -> Element<'a, Message> {
+    let label = if state.sort != column {
+        label.to_string()
+    } else if state.descending {
+        format!("{label} ▼")
+    } else {
+        format!("{label} ▲")
+    };
+
+    button(text(label).size(14.0))
+        .style(button::secondary)
+        .width(Length::Fixed(width))
+        .on_press(LevelTableMessage::SortBy(column).into())
+        .into()
+}
+
+fn cell<'a>(content: String, width: f32) -> Element<'a, Message> {
+    container(text(content).size(14.0))
+        .padding([0.0, 4.0])
+        .width(Length::Fixed(width))
+        .into()
+}
+
+fn story_label(level: &LevelData) -> &'static str {
+    match story_rank(level) {
+        3 => "Completed",
+        2 => "Skipped",
+        1 => "Attempted",
+        _ => "-",
+    }
+}
+
+fn story_rank(level: &LevelData) -> u8 {
+    if level.was_completed_in_story_mode {
+        3
+    } else if level.was_skipped_in_story_mode {
+        2
+    } else if level.was_attempted_in_story_mode {
+        1
+    } else {
+        0
+    }
+}
+
+fn total_diamonds(level: &LevelData) -> u64 {
+    level
+        .awarded_diamonds
+        .saturating_add(level.awarded_diamonds_remix)
+}
+
+/// Lower is better, missing difficulties sort last.
+fn grade_rank(level: &LevelData, difficulty: u64) -> usize {
+    level
+        .difficulty_data
+        .iter()
+        .find(|x| x.difficulty == difficulty)
+        .map(|x| catalog::grade_rank(&x.letter_grade))
+        .unwrap_or(usize::MAX)
+}
+
+impl Into<Message> for LevelTableMessage {
+    fn into(self) -> Message {
+        Message::Editor(EditorMessage::LevelTable(self))
+    }
+}
@@ -0,0 +1,2129 @@
+use std::{borrow::Cow, fmt::Display, ops::RangeInclusive, path::PathBuf};
+
+use filter::{EditorFilter, FilterMessage};
+use iced::{
+    Element, Length, Task,
+    alignment::Vertical,
+    color,
+    widget::{
+        button, checkbox, column, container, pick_list, responsive, row, scrollable, text,
+        text_editor, text_input,
+    },
+};
+use level_table::{LevelLayout, LevelTableMessage, LevelTableState};
+use raw_json::JsonState;
+use rfd::AsyncFileDialog;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use storyline::StorylineEditMessage;
+use thiserror::Error;
+
+use crate::{
+    Message, PendingAction,
+    catalog::{self, PickerKind},
+    diff::{self, Change, Segment},
+    field::{Entry, FieldAddress, FieldError, FieldValue},
+    history::History,
+    macros::{self, Macro, Skipped, Step},
+    modals::{
+        dialog::DialogState, numeric_field_editor::NumericFieldEditorInit, picker::PickerInit,
+    },
+    models::{DifficultyData, LevelData, SaveGame},
+    recovery,
+    save_file::{self, Encoding},
+    templates,
+    versions::{self, Version, VersionError},
+};
+
+mod changes;
+mod completion_matrix;
+mod enemies;
+mod filter;
+mod level_table;
+mod raw_json;
+mod storyline;
+
+pub struct EditorState {
+    data: SaveGame,
+    original: SaveGame,
+    path: PathBuf,
+    /// How the loaded file was stored, used for every file written.
+    encoding: Encoding,
+    error: Option<String>,
+    filter: EditorFilter,
+    levels: LevelTableState,
+    history: History<SaveGame>,
+    /// Differences between `original` and `data`.
+    changes: Vec<Change>,
+    /// Edits made since recording a macro was started.
+    recording: Option<Macro>,
+    tab: EditorTab,
+    json: JsonState,
+    /// Relative vertical position of the fields tab.
+    scroll: f32,
+    /// Changes in the recovery snapshot last written for this save.
+    snapshot: Vec<Change>,
+    /// Whether a snapshot left by an earlier session wasn't restored or
+    /// discarded yet. It's neither replaced nor deleted until it is.
+    recovery_pending: bool,
+}
+
+/// How the editor was set up when the app was closed, to pick up where it
+/// was left on the next launch.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EditorSession {
+    tab: EditorTab,
+    filter: EditorFilter,
+    levels: LevelTableState,
+    scroll: f32,
+}
+
+/// How the save is shown in the main column.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum EditorTab {
+    #[default]
+    Fields,
+    Json,
+}
+
+impl EditorState {
+    pub fn new(save: SaveGame, path: PathBuf, encoding: Encoding) -> Self {
+        EditorState {
+            data: save.clone(),
+            original: save,
+            path,
+            encoding,
+            error: None,
+            filter: EditorFilter::default(),
+            levels: LevelTableState::default(),
+            history: History::default(),
+            changes: Vec::new(),
+            recording: None,
+            tab: EditorTab::default(),
+            json: JsonState::default(),
+            scroll: 0.0,
+            snapshot: Vec::new(),
+            recovery_pending: false,
+        }
+    }
+
+    pub fn data(&self) -> &SaveGame {
+        &self.data
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn session(&self) -> EditorSession {
+        EditorSession {
+            tab: self.tab,
+            filter: self.filter.clone(),
+            levels: self.levels.clone(),
+            scroll: self.scroll,
+        }
+    }
+
+    /// Brings back the tab, filters, layout and scroll position of an
+    /// earlier session. The fields are scrolled there by [`Self::snap_scroll`].
+    pub fn restore(&mut self, session: EditorSession) {
+        self.tab = session.tab;
+        self.filter = session.filter;
+        self.levels = session.levels;
+        self.scroll = session.scroll;
+
+        // The save may have changed since, so the selection has to still
+        // point at a level.
+        if self
+            .levels
+            .selected
+            .is_some_and(|index| index >= self.data.level_data.len())
+        {
+            self.levels.selected = None;
+            self.levels.focused = None;
+        }
+
+        if self.tab == EditorTab::Json {
+            self.json.sync(&self.data);
+        }
+    }
+
+    /// Scrolls the fields to where this editor last had them. Every tab
+    /// shows its fields in the same scrollable, so this is needed whenever
+    /// another editor is brought up.
+    pub fn snap_scroll(&self) -> Task<Message> {
+        scrollable::snap_to(
+            fields_scroll_id(),
+            scrollable::RelativeOffset {
+                x: 0.0,
+                y: self.scroll,
+            },
+        )
+    }
+
+    pub fn changes(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// The edited save followed by the one it started from.
+    pub fn saves(&self) -> [&SaveGame; 2] {
+        [&self.data, &self.original]
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Writes the current data to `path` and makes it the file the editor
+    /// keeps saving to from now on. Failures also open an alert.
+    fn save_to(&mut self, path: PathBuf) -> Task<Message> {
+        match save_file::write(&path, &self.data, self.encoding) {
+            Ok(()) => {
+                self.remove_recovery();
+                self.snapshot.clear();
+
+                self.original = self.data.clone();
+                self.path = path;
+                self.error = None;
+                Task::none()
+            }
+            Err(err) => {
+                let error = format!("Failed to save {}: {}", path.display(), err);
+                self.error = Some(error.clone());
+                Task::done(Message::OpenDialog(DialogState::alert(
+                    "Couldn't save",
+                    error,
+                )))
+            }
+        }
+    }
+
+    /// Brings the recovery snapshot in line with the pending changes,
+    /// deleting it once there are none.
+    pub fn write_recovery(&mut self) {
+        if self.recovery_pending || self.changes == self.snapshot {
+            return;
+        }
+
+        if self.changes.is_empty() {
+            recovery::remove(&self.path);
+        } else if let Err(err) = recovery::write(&self.path, &self.changes) {
+            tracing::warn!("Failed to write the recovery snapshot: {}", err);
+            return;
+        }
+
+        self.snapshot = self.changes.clone();
+    }
+
+    /// Keeps the snapshot an earlier session left for this save until it's
+    /// restored or discarded.
+    pub fn hold_recovery(&mut self) {
+        self.recovery_pending = true;
+    }
+
+    /// Deletes the recovery snapshot once the edits were saved or thrown
+    /// away, unless it's one still waiting to be restored.
+    pub fn remove_recovery(&self) {
+        if !self.recovery_pending {
+            recovery::remove(&self.path);
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| "SaveGame".into())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum EditorMessage {
+    Save,
+    SaveAs,
+    SaveTo(PathBuf),
+    ExportToDirectory,
+    ExportTo(PathBuf),
+    Undo,
+    Redo,
+    Filter(FilterMessage),
+    LevelTable(LevelTableMessage),
+    /// Brings up the field or entry at the path of a pending change.
+    GoToChange(diff::Path),
+    MarkAllFullCombo,
+    SetField(FieldAddress, FieldValue),
+    LevelEdit {
+        index: usize,
+        message: LevelEditMessage,
+    },
+    AddLevel(NewLevel),
+    RemoveLevel(usize),
+    DuplicateLevel(usize),
+    AddStoryline,
+    RemoveStoryline(usize),
+    StorylineEdit {
+        index: usize,
+        message: StorylineEditMessage,
+    },
+    Revert(RevertTarget),
+    AddEnemy,
+    RemoveEnemy(usize),
+    StartRecording,
+    /// Stops recording and asks where to save the macro.
+    StopRecording,
+    /// Goes on recording `Macro` after its save dialog was cancelled.
+    ResumeRecording(Macro),
+    SaveMacroTo(PathBuf, Macro),
+    ReplayMacro,
+    ReplayMacroFrom(PathBuf),
+    SetTab(EditorTab),
+    /// The fields tab was scrolled to this relative position.
+    Scrolled(f32),
+    /// Text editor actions only mean something to the widget they came
+    /// from, so this can't be serialized. Valid edits end up as
+    /// `ReplaceData`.
+    #[serde(skip)]
+    EditJson(text_editor::Action),
+    GoToJsonError,
+    /// Replaces the whole save, as parsed from the JSON tab.
+    ReplaceData(Box<SaveGame>),
+    /// Makes changes found between two versions of a save again, leaving
+    /// the rest of the save as it is. Macros record edits that depend on
+    /// more than the save, such as reverts, as the changes they made.
+    ApplyChanges(Vec<Change>),
+    /// Upgrades or downgrades the save to another known version.
+    Migrate(Version),
+    /// Makes the changes of a recovery snapshot again.
+    RestoreRecovery(Vec<Change>),
+    /// Deletes the recovery snapshot instead of restoring it.
+    DiscardRecovery,
+}
+
+#[derive(Debug, Error)]
+pub enum EditError {
+    #[error(transparent)]
+    Field(#[from] FieldError),
+    #[error(transparent)]
+    Version(#[from] VersionError),
+    #[error("The migrated save doesn't fit the editor's format: {0}")]
+    Incompatible(#[source] serde_json::Error),
+    #[error("{0} of the recovered changes no longer fit the save and were left out")]
+    Unrecoverable(usize),
+    #[error("{0} of the changes don't fit the save and were left out")]
+    Unapplied(usize),
+}
+
+/// Part of the save to set back to what it was when it was loaded or last
+/// saved. Entries that didn't exist back then are removed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum RevertTarget {
+    All,
+    Level(usize),
+    Difficulty(usize, DifficultySlot),
+    /// A single entry of the pending changes.
+    Change(diff::Path),
+}
+
+/// Where the entry for a newly added level comes from.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum NewLevel {
+    Blank,
+    /// A level the save refers to without having an entry for it.
+    FromCatalog(String),
+}
+
+impl Display for NewLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NewLevel::Blank => write!(f, "Blank level"),
+            NewLevel::FromCatalog(level_id) => write!(f, "{level_id}"),
+        }
+    }
+}
+
+impl EditorState {
+    pub fn update(&mut self, message: EditorMessage) -> Task<Message> {
+        let mut task = Task::none();
+
+        // Messages that only change how the save is shown return early, the
+        // changes and the JSON text at the end only follow the data.
+        match message {
+            EditorMessage::Save => task = self.save_to(self.path.clone()),
+            EditorMessage::SaveAs => {
+                let mut dialog = AsyncFileDialog::new().set_file_name(self.file_name());
+                if let Some(directory) = self.path.parent() {
+                    dialog = dialog.set_directory(directory);
+                }
+
+                return Task::future(async move {
+                    let Some(file) = dialog.save_file().await else {
+                        return Message::None;
+                    };
+
+                    EditorMessage::SaveTo(file.path().to_path_buf()).into()
+                });
+            }
+            EditorMessage::SaveTo(path) => task = self.save_to(path),
+            EditorMessage::ExportToDirectory => {
+                return Task::future(async {
+                    let Some(folder) = AsyncFileDialog::new().pick_folder().await else {
+                        return Message::None;
+                    };
+
+                    EditorMessage::ExportTo(folder.path().to_path_buf()).into()
+                });
+            }
+            EditorMessage::ExportTo(directory) => {
+                let path = directory.join(self.file_name());
+                task = self.save_to(path);
+            }
+            EditorMessage::Filter(message) => {
+                self.filter.update(message);
+                return Task::none();
+            }
+            EditorMessage::LevelTable(message) => {
+                self.levels.update(message);
+                return Task::none();
+            }
+            EditorMessage::GoToChange(path) => {
+                self.go_to(&path);
+                return Task::none();
+            }
+            EditorMessage::Undo => {
+                if let Some(data) = self.history.undo(self.data.clone()) {
+                    self.data = data;
+                    self.record(Step::new(EditorMessage::Undo, &self.data));
+                }
+            }
+            EditorMessage::Redo => {
+                if let Some(data) = self.history.redo(self.data.clone()) {
+                    self.data = data;
+                    self.record(Step::new(EditorMessage::Redo, &self.data));
+                }
+            }
+            EditorMessage::SetTab(tab) => {
+                self.tab = tab;
+                if tab == EditorTab::Json {
+                    self.json.sync(&self.data);
+                }
+                return Task::none();
+            }
+            EditorMessage::Scrolled(scroll) => {
+                self.scroll = scroll;
+                return Task::none();
+            }
+            EditorMessage::EditJson(action) => {
+                let edited = self.json.perform(action);
+                if let Some(save) = edited.filter(|save| *save != self.data) {
+                    return self.update(EditorMessage::ReplaceData(Box::new(save)));
+                }
+                return Task::none();
+            }
+            EditorMessage::GoToJsonError => {
+                self.json.go_to_error();
+                return Task::none();
+            }
+            EditorMessage::DiscardRecovery => {
+                self.recovery_pending = false;
+                self.remove_recovery();
+                return Task::none();
+            }
+            EditorMessage::StartRecording => {
+                self.recording = Some(Macro::default());
+                return Task::none();
+            }
+            EditorMessage::StopRecording => {
+                let Some(recorded) = self.recording.take() else {
+                    return Task::none();
+                };
+                if recorded.steps.is_empty() {
+                    return Task::none();
+                }
+
+                let dialog = AsyncFileDialog::new()
+                    .add_filter("Macro", &["json"])
+                    .set_file_name("macro.json");
+
+                return Task::future(async move {
+                    match dialog.save_file().await {
+                        Some(file) => {
+                            EditorMessage::SaveMacroTo(file.path().to_path_buf(), recorded).into()
+                        }
+                        None => EditorMessage::ResumeRecording(recorded).into(),
+                    }
+                });
+            }
+            EditorMessage::ResumeRecording(recorded) => {
+                self.recording = Some(recorded);
+                return Task::none();
+            }
+            EditorMessage::SaveMacroTo(path, recorded) => {
+                if let Err(err) = macros::write(&path, &recorded) {
+                    let error = format!("Failed to save {}: {}", path.display(), err);
+                    self.error = Some(error.clone());
+                    return Task::done(Message::OpenDialog(DialogState::alert(
+                        "Couldn't save the macro",
+                        error,
+                    )));
+                }
+                return Task::none();
+            }
+            EditorMessage::ReplayMacro => {
+                return Task::future(async {
+                    let Some(file) = AsyncFileDialog::new()
+                        .add_filter("Macro", &["json"])
+                        .pick_file()
+                        .await
+                    else {
+                        return Message::None;
+                    };
+
+                    EditorMessage::ReplayMacroFrom(file.path().to_path_buf()).into()
+                });
+            }
+            EditorMessage::ReplayMacroFrom(path) => match macros::load(&path) {
+                Ok(recorded) => {
+                    let skipped = self.replay(&recorded);
+                    if !skipped.is_empty() {
+                        let body = skipped
+                            .iter()
+                            .map(Skipped::to_string)
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        task = Task::done(Message::OpenDialog(DialogState::alert(
+                            format!(
+                                "Skipped {} of {} steps",
+                                skipped.len(),
+                                recorded.steps.len()
+                            ),
+                            body,
+                        )));
+                    }
+                }
+                Err(err) => {
+                    let error = format!("Failed to load {}: {}", path.display(), err);
+                    self.error = Some(error.clone());
+                    task = Task::done(Message::OpenDialog(DialogState::alert(
+                        "Couldn't load the macro",
+                        error,
+                    )));
+                }
+            },
+            message => {
+                let key = message.merge_key();
+                let before = self.data.clone();
+                let step = self
+                    .recording
+                    .is_some()
+                    .then(|| Step::new(message.clone(), &self.data))
+                    .flatten();
+
+                match &message {
+                    EditorMessage::RemoveLevel(index) => self.levels.level_removed(*index),
+                    EditorMessage::AddLevel(_) => {
+                        self.levels.select(self.data.level_data.len());
+                    }
+                    EditorMessage::DuplicateLevel(index) => self.levels.select(index + 1),
+                    EditorMessage::Revert(RevertTarget::Level(index))
+                        if self.data.level_data.get(*index).is_some_and(|level| {
+                            diff::match_level(&self.original.level_data, level).is_none()
+                        }) =>
+                    {
+                        self.levels.level_removed(*index)
+                    }
+                    _ => {}
+                }
+
+                if let Err(err) = self.apply(message) {
+                    self.error = Some(err.to_string());
+                }
+
+                if self.data != before {
+                    // Replayed as they are, these would do something else to
+                    // the save the macro is replayed on, so only what they
+                    // changed is kept.
+                    let step = match step {
+                        Some(step) if step.message.depends_on_editor() => Step::new(
+                            EditorMessage::ApplyChanges(diff::diff(&before, &self.data)),
+                            &before,
+                        ),
+                        step => step,
+                    };
+
+                    self.history.record(before, key);
+                    self.record(step);
+                }
+            }
+        }
+
+        self.levels.clamp(self.data.level_data.len());
+
+        self.changes = diff::diff(&self.original, &self.data);
+
+        if self.tab == EditorTab::Json {
+            self.json.sync(&self.data);
+        }
+
+        task
+    }
+
+    /// Adds `step` to the macro being recorded, if any.
+    fn record(&mut self, step: Option<Step>) {
+        if let (Some(recording), Some(step)) = (&mut self.recording, step) {
+            recording.steps.push(step);
+        }
+    }
+
+    /// Replays `recorded` onto the save as a single undo step. Returns the
+    /// steps whose targets the save doesn't have, or that failed otherwise.
+    pub fn replay(&mut self, recorded: &Macro) -> Vec<Skipped> {
+        let before = self.data.clone();
+        // Undo and redo in a macro only go back through its own steps.
+        let mut history = History::default();
+        let mut skipped = Vec::new();
+
+        for (index, step) in recorded.steps.iter().enumerate() {
+            let result = match &step.message {
+                EditorMessage::Undo => match history.undo(self.data.clone()) {
+                    Some(data) => {
+                        self.data = data;
+                        Ok(())
+                    }
+                    None => Err("Nothing to undo".to_string()),
+                },
+                EditorMessage::Redo => match history.redo(self.data.clone()) {
+                    Some(data) => {
+                        self.data = data;
+                        Ok(())
+                    }
+                    None => Err("Nothing to redo".to_string()),
+                },
+                _ => step.resolve(&self.data).and_then(|message| {
+                    let key = message.merge_key();
+                    let before = self.data.clone();
+                    self.apply(message).map_err(|err| err.to_string())?;
+
+                    if self.data != before {
+                        history.record(before, key);
+                    }
+                    Ok(())
+                }),
+            };
+
+            match result {
+                Ok(()) => self.record(Some(step.clone())),
+                Err(reason) => skipped.push(Skipped {
+                    step: index + 1,
+                    reason,
+                }),
+            }
+        }
+
+        if self.data != before {
+            self.history.record(before, None);
+        }
+
+        self.levels.clamp(self.data.level_data.len());
+        self.changes = diff::diff(&self.original, &self.data);
+
+        skipped
+    }
+
+    /// Selects the level, and difficulty if any, a change was made to. Other
+    /// fields are brought up through the search.
+    fn go_to(&mut self, path: &diff::Path) {
+        match path.0.as_slice() {
+            [
+                Segment::Field(field),
+                Segment::Key {
+                    value: Value::String(level_id),
+                    ..
+                },
+                rest @ ..,
+            ] if field == "LevelDatas" => {
+                let Some(index) = self
+                    .data
+                    .level_data
+                    .iter()
+                    .position(|x| &x.level_id == level_id)
+                else {
+                    return;
+                };
+                let level = &self.data.level_data[index];
+
+                let slot = match rest {
+                    [Segment::Field(field), Segment::Key { value, .. }, ..] => {
+                        let remix = field == "RemixDifficultyHighScoreDatas";
+                        let data = if remix {
+                            &level.remix_difficulty_data
+                        } else {
+                            &level.difficulty_data
+                        };
+
+                        data.iter()
+                            .position(|x| value.as_u64() == Some(x.difficulty))
+                            .map(|index| DifficultySlot { remix, index })
+                    }
+                    _ => None,
+                };
+
+                match slot {
+                    Some(slot) => self.levels.update(LevelTableMessage::Focus(index, slot)),
+                    None => self.levels.select(index),
+                }
+            }
+            [Segment::Field(field), ..] => {
+                if let Some(query) = changes::field_query(field) {
+                    self.filter.update(FilterMessage::EditQuery(query));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn revert(&mut self, target: RevertTarget) {
+        match target {
+            RevertTarget::All => self.data = self.original.clone(),
+            RevertTarget::Change(path) => {
+                let reverted = self
+                    .changes
+                    .iter()
+                    .find(|change| change.path == path)
+                    .and_then(|change| diff::revert(&self.data, change));
+
+                if let Some(data) = reverted {
+                    self.data = data;
+                }
+            }
+            RevertTarget::Level(index) => {
+                let Some(level) = self.data.level_data.get(index) else {
+                    return;
+                };
+
+                match diff::match_level(&self.original.level_data, level) {
+                    Some(original) => self.data.level_data[index] = original.clone(),
+                    None => {
+                        self.data.level_data.remove(index);
+                    }
+                }
+            }
+            RevertTarget::Difficulty(index, slot) => {
+                let Some(level) = self.data.level_data.get_mut(index) else {
+                    return;
+                };
+                let original = diff::match_level(&self.original.level_data, level);
+
+                let (data, original) = if slot.remix {
+                    (
+                        &mut level.remix_difficulty_data,
+                        original.map(|x| &x.remix_difficulty_data),
+                    )
+                } else {
+                    (
+                        &mut level.difficulty_data,
+                        original.map(|x| &x.difficulty_data),
+                    )
+                };
+
+                let Some(difficulty) = data.get(slot.index) else {
+                    return;
+                };
+
+                match original.and_then(|original| diff::match_difficulty(original, difficulty)) {
+                    Some(original) => data[slot.index] = original.clone(),
+                    None => {
+                        data.remove(slot.index);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies a single edit to `data`. Every message that reaches this goes
+    /// through the undo history as one step.
+    fn apply(&mut self, message: EditorMessage) -> Result<(), EditError> {
+        match message {
+            EditorMessage::SetField(field, value) => field.set(&mut self.data, value)?,
+            EditorMessage::LevelEdit { index, message } => {
+                let Some(level) = self.data.level_data.get_mut(index) else {
+                    return Err(FieldError::MissingEntry(Entry::Level(index)).into());
+                };
+
+                match message {
+                    LevelEditMessage::AddDifficulty { remix, difficulty } => {
+                        let data = if remix {
+                            &mut level.remix_difficulty_data
+                        } else {
+                            &mut level.difficulty_data
+                        };
+                        data.push(templates::difficulty(difficulty));
+                        data.sort_by_key(|x| x.difficulty);
+                    }
+                    LevelEditMessage::RemoveDifficulty(slot) => {
+                        let data = if slot.remix {
+                            &mut level.remix_difficulty_data
+                        } else {
+                            &mut level.difficulty_data
+                        };
+                        if slot.index < data.len() {
+                            data.remove(slot.index);
+                        }
+                    }
+                }
+            }
+            EditorMessage::AddLevel(new) => {
+                let level_id = match new {
+                    NewLevel::Blank => templates::unique_level_id(&self.data, "NewLevel"),
+                    NewLevel::FromCatalog(level_id) => level_id,
+                };
+                self.data.level_data.push(templates::level(level_id));
+            }
+            EditorMessage::RemoveLevel(index) => {
+                if index < self.data.level_data.len() {
+                    self.data.level_data.remove(index);
+                }
+            }
+            EditorMessage::DuplicateLevel(index) => {
+                let Some(level) = self.data.level_data.get(index) else {
+                    return Err(FieldError::MissingEntry(Entry::Level(index)).into());
+                };
+
+                let mut copy = level.clone();
+                copy.level_id = templates::unique_level_id(&self.data, &level.level_id);
+                self.data.level_data.insert(index + 1, copy);
+            }
+            EditorMessage::Revert(target) => self.revert(target),
+            EditorMessage::ReplaceData(save) => self.data = *save,
+            EditorMessage::Migrate(target) => {
+                let mut value =
+                    serde_json::to_value(&self.data).map_err(EditError::Incompatible)?;
+                versions::migrate(&mut value, target)?;
+                self.data = serde_json::from_value(value).map_err(EditError::Incompatible)?;
+            }
+            EditorMessage::ApplyChanges(changes) => {
+                let (data, skipped) = diff::reapply(&self.data, &changes);
+                self.data = data;
+
+                if skipped > 0 {
+                    return Err(EditError::Unapplied(skipped));
+                }
+            }
+            EditorMessage::RestoreRecovery(changes) => {
+                self.recovery_pending = false;
+                let (data, skipped) = diff::reapply(&self.data, &changes);
+                self.data = data;
+
+                if skipped > 0 {
+                    return Err(EditError::Unrecoverable(skipped));
+                }
+            }
+            EditorMessage::AddStoryline => {
+                let characters = templates::next_storyline_characters(&self.data);
+                self.data
+                    .storyline_data
+                    .push(templates::storyline(characters));
+            }
+            EditorMessage::RemoveStoryline(index) => {
+                if index < self.data.storyline_data.len() {
+                    self.data.storyline_data.remove(index);
+                }
+            }
+            EditorMessage::StorylineEdit { index, message } => {
+                if let Some(storyline) = self.data.storyline_data.get_mut(index) {
+                    storyline::apply(storyline, message);
+                }
+            }
+            EditorMessage::AddEnemy => {
+                let enemy_id = templates::next_enemy_id(&self.data);
+                self.data
+                    .enemy_kill_counts_by_id
+                    .push(templates::enemy(enemy_id));
+            }
+            EditorMessage::RemoveEnemy(index) => {
+                if index < self.data.enemy_kill_counts_by_id.len() {
+                    self.data.enemy_kill_counts_by_id.remove(index);
+                }
+            }
+            EditorMessage::MarkAllFullCombo => {
+                for level in &mut self.data.level_data {
+                    level.was_attempted_in_story_mode = true;
+                    level.was_completed_in_story_mode = true;
+                    for difficulty in &mut level.difficulty_data {
+                        difficulty.num_attempts = difficulty.num_attempts.min(1);
+                        difficulty.num_retries = difficulty.num_retries.min(1);
+                        difficulty.has_all_perfects = true;
+                        difficulty.has_full_combo_rhythm_rift = true;
+                    }
+                }
+            }
+            EditorMessage::Save
+            | EditorMessage::SaveAs
+            | EditorMessage::SaveTo(_)
+            | EditorMessage::ExportToDirectory
+            | EditorMessage::ExportTo(_)
+            | EditorMessage::Undo
+            | EditorMessage::Redo
+            | EditorMessage::Filter(_)
+            | EditorMessage::LevelTable(_)
+            | EditorMessage::GoToChange(_)
+            | EditorMessage::StartRecording
+            | EditorMessage::StopRecording
+            | EditorMessage::ResumeRecording(_)
+            | EditorMessage::SaveMacroTo(..)
+            | EditorMessage::ReplayMacro
+            | EditorMessage::ReplayMacroFrom(_)
+            | EditorMessage::SetTab(_)
+            | EditorMessage::Scrolled(_)
+            | EditorMessage::DiscardRecovery
+            | EditorMessage::EditJson(_)
+            | EditorMessage::GoToJsonError => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl EditorMessage {
+    /// Whether the edit's outcome depends on more than the save it's applied
+    /// to, such as the loaded save a revert goes back to or the whole save
+    /// typed into the JSON tab.
+    fn depends_on_editor(&self) -> bool {
+        matches!(
+            self,
+            EditorMessage::ReplaceData(_)
+                | EditorMessage::Revert(_)
+                | EditorMessage::RestoreRecovery(_)
+        )
+    }
+
+    /// Consecutive edits with the same key are merged into one undo step, so
+    /// typing into a text field doesn't record every keystroke.
+    fn merge_key(&self) -> Option<String> {
+        match self {
+            EditorMessage::SetField(field, FieldValue::Text(_)) => Some(field.to_string()),
+            EditorMessage::ReplaceData(_) => Some("json".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Entry of the save the edit is about, if it's about a single one.
+    pub fn entry(&self) -> Option<Entry> {
+        match self {
+            EditorMessage::SetField(field, _) => Some(field.entry),
+            EditorMessage::LevelEdit {
+                index,
+                message: LevelEditMessage::RemoveDifficulty(slot),
+            }
+            | EditorMessage::Revert(RevertTarget::Difficulty(index, slot)) => {
+                Some(slot.entry(*index))
+            }
+            EditorMessage::LevelEdit { index, .. }
+            | EditorMessage::RemoveLevel(index)
+            | EditorMessage::DuplicateLevel(index)
+            | EditorMessage::Revert(RevertTarget::Level(index)) => Some(Entry::Level(*index)),
+            EditorMessage::StorylineEdit {
+                index,
+                message: StorylineEditMessage::RemoveStoryBeat(beat),
+            } => Some(Entry::StoryBeat {
+                storyline: *index,
+                index: *beat,
+            }),
+            EditorMessage::StorylineEdit { index, .. } | EditorMessage::RemoveStoryline(index) => {
+                Some(Entry::Storyline(*index))
+            }
+            EditorMessage::RemoveEnemy(index) => Some(Entry::Enemy(*index)),
+            _ => None,
+        }
+    }
+
+    /// The same edit made to `entry` instead, which has to be the same kind
+    /// of entry [`EditorMessage::entry`] returns.
+    pub fn with_entry(mut self, entry: Entry) -> Self {
+        match (&mut self, entry) {
+            (EditorMessage::SetField(field, _), entry) => field.entry = entry,
+            (
+                EditorMessage::LevelEdit {
+                    index,
+                    message: LevelEditMessage::RemoveDifficulty(slot),
+                }
+                | EditorMessage::Revert(RevertTarget::Difficulty(index, slot)),
+                Entry::Difficulty {
+                    level,
+                    remix,
+                    index: difficulty,
+                },
+            ) => {
+                *index = level;
+                *slot = DifficultySlot {
+                    remix,
+                    index: difficulty,
+                };
+            }
+            (
+                EditorMessage::LevelEdit { index, .. }
+                | EditorMessage::RemoveLevel(index)
+                | EditorMessage::DuplicateLevel(index)
+                | EditorMessage::Revert(RevertTarget::Level(index)),
+                Entry::Level(level),
+            ) => *index = level,
+            (
+                EditorMessage::StorylineEdit {
+                    index,
+                    message: StorylineEditMessage::RemoveStoryBeat(beat),
+                },
+                Entry::StoryBeat {
+                    storyline,
+                    index: beat_index,
+                },
+            ) => {
+                *index = storyline;
+                *beat = beat_index;
+            }
+            (
+                EditorMessage::StorylineEdit { index, .. } | EditorMessage::RemoveStoryline(index),
+                Entry::Storyline(storyline),
+            ) => *index = storyline,
+            (EditorMessage::RemoveEnemy(index), Entry::Enemy(enemy)) => *index = enemy,
+            _ => {}
+        }
+
+        self
+    }
+}
+
+pub fn view(state: &EditorState) -> Element<Message> {
+    let version = Version {
+        game_data: state.data.game_data_version,
+        save_data: state.data.save_data_version,
+    };
+    let targets = version.targets();
+    let version_notice = (!version.is_known() || !targets.is_empty()).then(|| {
+        row![]
+            .push_maybe((!version.is_known()).then(|| {
+                text(format!(
+                    "Version {version} isn't one the editor knows, some fields may be missing or misread"
+                ))
+                .color(color!(0xCC8800))
+                .width(Length::Fill)
+            }))
+            .push_maybe((!targets.is_empty()).then(|| {
+                pick_list(targets, None::<Version>, |x| EditorMessage::Migrate(x).into())
+                    .placeholder("Migrate to version...")
+            }))
+            .align_y(Vertical::Center)
+            .spacing(4.0)
+    });
+
+    let general = Fields::new(&state.filter)
+        .str(
+            "SaveName",
+            &state.data.save_name,
+            &state.original.save_name,
+            "SaveName",
+        )
+        .str(
+            "PlayerID",
+            &state.data.player_id,
+            &state.original.player_id,
+            "PlayerID",
+        )
+        .pick(
+            "Selected Language",
+            &state.data.selected_language,
+            &state.original.selected_language,
+            PickerKind::Language,
+            "SelectedLanguage",
+        )
+        .pick(
+            "Active Cosmetic Pin",
+            &state.data.active_cosmetic_pin,
+            &state.original.active_cosmetic_pin,
+            PickerKind::Pin,
+            "ActiveCosmeticPin",
+        )
+        .pick(
+            "Active Gameplay Pin",
+            &state.data.active_gameplay_pin,
+            &state.original.active_gameplay_pin,
+            PickerKind::Pin,
+            "ActiveGameplayPin",
+        )
+        .bool(
+            "Should Display Dialogue Debug",
+            state.data.should_display_dialogue_debug,
+            state.original.should_display_dialogue_debug,
+            "ShouldDisplayDialogueDebug",
+        )
+        .bool(
+            "Should Unlock All Levels",
+            state.data.should_unlock_all_levels,
+            state.original.should_unlock_all_levels,
+            "ShouldUnlockAllLevels",
+        )
+        .bool(
+            "Has Input Dragon Dance",
+            state.data.has_input_dragon_dance,
+            state.original.has_input_dragon_dance,
+            "HasInputDragonDance",
+        )
+        .bool(
+            "Is Remix Mode Active",
+            state.data.is_remix_mode_active,
+            state.original.is_remix_mode_active,
+            "IsRemixModeActive",
+        )
+        .bool(
+            "Should Play All Story Content In Order",
+            state.data.should_play_all_story_content_in_order,
+            state.original.should_play_all_story_content_in_order,
+            "ShouldPlayAllStoryContentInOrder",
+        )
+        .num(
+            "Game Data Version",
+            state.data.game_data_version,
+            state.original.game_data_version,
+            "GameDataVersion",
+        )
+        .num(
+            "Save Data Version",
+            state.data.save_data_version,
+            state.original.save_data_version,
+            "SaveDataVersion",
+        )
+        .num(
+            "Times Booted",
+            state.data.times_booted,
+            state.original.times_booted,
+            "TimesBooted",
+        )
+        .num(
+            "Save ID",
+            state.data.save_id,
+            state.original.save_id,
+            "SaveID",
+        )
+        .num_in(
+            "Framerate Limit",
+            state.data.framerate_limit,
+            state.original.framerate_limit,
+            FRAMERATE_LIMIT,
+            "FramerateLimit",
+        )
+        .pick_num(
+            "Selected Story Difficulty",
+            state.data.selected_story_difficulty,
+            state.original.selected_story_difficulty,
+            PickerKind::Difficulty,
+            "SelectedStoryDifficulty",
+        )
+        .pick_num(
+            "Selected Arcade Difficulty",
+            state.data.selected_arcade_difficulty,
+            state.original.selected_arcade_difficulty,
+            PickerKind::Difficulty,
+            "SelectedArcadeDifficulty",
+        )
+        .num(
+            "Total Rhythm Rifts Cleared",
+            state.data.total_rhythm_rifts_cleared,
+            state.original.total_rhythm_rifts_cleared,
+            "TotalRhythmRiftsCleared",
+        )
+        .num(
+            "Total Diamonds",
+            state.data.total_diamonds,
+            state.original.total_diamonds,
+            "TotalDiamonds",
+        )
+        .num(
+            "Total Vibe Power Uses",
+            state.data.total_vibe_power_uses,
+            state.original.total_vibe_power_uses,
+            "TotalVibePowerUses",
+        )
+        .num(
+            "Max Enemies Killed While Vibing",
+            state.data.max_enemies_killed_while_vibing,
+            state.original.max_enemies_killed_while_vibing,
+            "MaxEnemiesKilledWhileVibing",
+        )
+        .num(
+            "BB Total Attacks",
+            state.data.bb_total_attacks,
+            state.original.bb_total_attacks,
+            "BBTotalAttacks",
+        )
+        .num(
+            "BB Total Dodges",
+            state.data.bb_total_dodges,
+            state.original.bb_total_dodges,
+            "BBTotalDodges",
+        )
+        .num(
+            "BB Total Blocked Hits",
+            state.data.bb_total_blocked_hits,
+            state.original.bb_total_blocked_hits,
+            "BBTotalBlockedHits",
+        )
+        .finish();
+
+    let new_levels = std::iter::once(NewLevel::Blank)
+        .chain(
+            catalog::missing_level_ids(&state.data)
+                .into_iter()
+                .map(NewLevel::FromCatalog),
+        )
+        .collect::<Vec<_>>();
+
+    let storylines_changed = state.data.storyline_data != state.original.storyline_data;
+    let show_storylines = state.filter.shows_field("Storylines", storylines_changed)
+        || state
+            .filter
+            .shows_field("StorylineDatas", storylines_changed);
+    let enemies_changed =
+        state.data.enemy_kill_counts_by_id != state.original.enemy_kill_counts_by_id;
+    let show_enemies = state
+        .filter
+        .shows_field("Enemy Kill Counts", enemies_changed)
+        || state
+            .filter
+            .shows_field("EnemyKillCountsById", enemies_changed);
+
+    let details = state.levels.selected.and_then(|index| {
+        let level = state.data.level_data.get(index)?;
+        let original = diff::match_level(&state.original.level_data, level);
+
+        Some(
+            column![
+                row![
+                    text(catalog::level_title(&level.level_id))
+                        .size(22.0)
+                        .width(Length::Fill),
+                    button("Revert").style(button::secondary).on_press_maybe(
+                        (original != Some(level))
+                            .then(|| { EditorMessage::Revert(RevertTarget::Level(index)).into() })
+                    ),
+                    button("Duplicate")
+                        .style(button::secondary)
+                        .on_press(EditorMessage::DuplicateLevel(index).into()),
+                    button("Delete")
+                        .style(button::danger)
+                        .on_press(EditorMessage::RemoveLevel(index).into()),
+                    button("Close")
+                        .style(button::secondary)
+                        .on_press(LevelTableMessage::CloseDetails.into())
+                ]
+                .align_y(Vertical::Center)
+                .spacing(4.0),
+                scrollable(level_edit(index, level, original, state.levels.focused))
+                    .spacing(4.0)
+                    .height(Length::Fill)
+            ]
+            .spacing(8.0)
+            .width(Length::FillPortion(2))
+            .height(Length::Fill),
+        )
+    });
+
+    container(
+        row![
+            column![
+                row![
+                    tab_button("Fields", state.tab, EditorTab::Fields),
+                    tab_button("JSON", state.tab, EditorTab::Json),
+                ]
+                .spacing(4.0),
+                match state.tab {
+                    EditorTab::Fields => scrollable(
+                        column![filter::view(&state.filter, &state.data.level_data)]
+                            .push_maybe(version_notice)
+                            .push_maybe((!general.is_empty()).then(|| text("General").size(22.0)))
+                            .push(column(general).spacing(8.0))
+                            .push(
+                                row![
+                                    text("Levels").size(22.0).width(Length::Fill),
+                                    pick_list(new_levels, None::<NewLevel>, |x| {
+                                        EditorMessage::AddLevel(x).into()
+                                    })
+                                    .placeholder("Add level..."),
+                                    layout_button("Table", &state.levels, LevelLayout::Table),
+                                    layout_button("Completion", &state.levels, LevelLayout::Matrix),
+                                ]
+                                .align_y(Vertical::Center)
+                                .spacing(4.0)
+                            )
+                            .push(match state.levels.layout {
+                                LevelLayout::Table => level_table::view(
+                                    &state.levels,
+                                    &state.filter,
+                                    &state.data.level_data,
+                                    &state.original.level_data,
+                                ),
+                                LevelLayout::Matrix => completion_matrix::view(
+                                    &state.levels,
+                                    &state.filter,
+                                    &state.data.level_data,
+                                    &state.original.level_data,
+                                ),
+                            })
+                            .push_maybe(show_storylines.then(|| {
+                                row![
+                                    text("Storylines").size(22.0).width(Length::Fill),
+                                    button("Add Storyline")
+                                        .style(button::secondary)
+                                        .on_press(EditorMessage::AddStoryline.into())
+                                ]
+                                .align_y(Vertical::Center)
+                            }))
+                            .push_maybe(show_storylines.then(|| {
+                                column(state.data.storyline_data.iter().enumerate().map(
+                                    |(index, data)| {
+                                        storyline::storyline_edit(
+                                            index,
+                                            data,
+                                            diff::match_storyline(
+                                                &state.original.storyline_data,
+                                                data,
+                                            ),
+                                        )
+                                    },
+                                ))
+                                .spacing(8.0)
+                            }))
+                            .push_maybe(show_enemies.then(|| {
+                                row![
+                                    text("Enemy Kill Counts").size(22.0).width(Length::Fill),
+                                    button("Add Enemy")
+                                        .style(button::secondary)
+                                        .on_press(EditorMessage::AddEnemy.into())
+                                ]
+                                .align_y(Vertical::Center)
+                            }))
+                            .push_maybe(show_enemies.then(|| {
+                                column(state.data.enemy_kill_counts_by_id.iter().enumerate().map(
+                                    |(index, data)| {
+                                        enemies::enemy_edit(
+                                            index,
+                                            data,
+                                            diff::match_enemy(
+                                                &state.original.enemy_kill_counts_by_id,
+                                                data,
+                                            ),
+                                        )
+                                    },
+                                ))
+                                .spacing(4.0)
+                            }))
+                            .spacing(8.0),
+                    )
+                    .id(fields_scroll_id())
+                    .on_scroll(|viewport| {
+                        EditorMessage::Scrolled(viewport.relative_offset().y).into()
+                    })
+                    .spacing(4.0)
+                    .height(Length::Fill)
+                    .into(),
+                    EditorTab::Json => raw_json::view(&state.json),
+                }
+            ]
+            .spacing(8.0)
+            .width(Length::FillPortion(3))
+            .height(Length::Fill),
+        ]
+        .push_maybe(details.filter(|_| state.tab == EditorTab::Fields))
+        .push_maybe((!state.changes.is_empty()).then(|| {
+            column![
+                text("Changes").size(22.0),
+                scrollable(changes::view(&state.changes, &state.data))
+                    .spacing(4.0)
+                    .height(Length::Fill)
+            ]
+            .spacing(8.0)
+            .width(Length::Fixed(320.0))
+            .height(Length::Fill)
+        }))
+        .push(
+            column![
+                text("Actions").size(22.0),
+                scrollable(
+                    column![
+                        row![
+                            button("Undo")
+                                .style(button::secondary)
+                                .width(Length::Fill)
+                                .on_press_maybe(
+                                    state.history.can_undo().then(|| EditorMessage::Undo.into())
+                                ),
+                            button("Redo")
+                                .style(button::secondary)
+                                .width(Length::Fill)
+                                .on_press_maybe(
+                                    state.history.can_redo().then(|| EditorMessage::Redo.into())
+                                ),
+                        ]
+                        .spacing(4.0),
+                        button("Revert All Changes")
+                            .style(button::danger)
+                            .width(Length::Fill)
+                            .on_press_maybe(
+                                state
+                                    .is_dirty()
+                                    .then(|| EditorMessage::Revert(RevertTarget::All).into())
+                            ),
+                        button("Mark all as Full Combo")
+                            .width(Length::Fill)
+                            .on_press(EditorMessage::MarkAllFullCombo.into()),
+                        match &state.recording {
+                            Some(recording) => button(text(format!(
+                                "Stop Recording ({} steps)...",
+                                recording.steps.len()
+                            )))
+                            .style(button::danger)
+                            .width(Length::Fill)
+                            .on_press(EditorMessage::StopRecording.into()),
+                            None => button("Record Macro")
+                                .style(button::secondary)
+                                .width(Length::Fill)
+                                .on_press(EditorMessage::StartRecording.into()),
+                        },
+                        button("Replay Macro...")
+                            .style(button::secondary)
+                            .width(Length::Fill)
+                            .on_press(EditorMessage::ReplayMacro.into()),
+                        button("Save As...")
+                            .style(button::secondary)
+                            .width(Length::Fill)
+                            .on_press(EditorMessage::SaveAs.into()),
+                        button("Export to Folder...")
+                            .style(button::secondary)
+                            .width(Length::Fill)
+                            .on_press(EditorMessage::ExportToDirectory.into()),
+                        button("Open Another Save...")
+                            .style(button::secondary)
+                            .width(Length::Fill)
+                            .on_press(Message::RequestAction(PendingAction::CloseSave)),
+                    ]
+                    .spacing(4.0)
+                    .padding(2.0)
+                )
+                .spacing(4.0)
+                .height(Length::Fill)
+                .width(Length::Fill),
+                container(responsive(|size| button(text(match state.changes.len() {
+                    0 => "Save".to_string(),
+                    1 => "Save (1 change)".to_string(),
+                    changes => format!("Save ({changes} changes)"),
+                }))
+                .style(button::success)
+                .width(Length::Fixed(size.width))
+                .height(Length::Shrink)
+                .on_press(EditorMessage::Save.into())
+                .into()))
+                .width(Length::Fill)
+                .height(Length::Fixed(45.0)),
+                row![
+                    text("Saving in"),
+                    text(state.path.display().to_string()).color(color!(1, 1, 1, 0.8))
+                ]
+                .spacing(2.0),
+                text(format!("as {}", state.encoding))
+                    .size(12.0)
+                    .color(color!(0x999999)),
+            ]
+            .push_maybe(
+                state
+                    .error
+                    .clone()
+                    .map(|error| text(error).color(color!(0xFF0000))),
+            )
+            .height(Length::Fill)
+            .width(Length::Shrink)
+            .max_width(350.0),
+        )
+        .height(Length::Fill)
+        .spacing(4.0),
+    )
+    .padding(12.0)
+    .into()
+}
+
+impl Into<Message> for EditorMessage {
+    fn into(self) -> Message {
+        Message::Editor(self)
+    }
+}
+
+/// The scrollable of the fields tab, scrolled back when a session is
+/// restored.
+fn fields_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("editor-fields")
+}
+
+fn tab_button(label: &str, current: EditorTab, tab: EditorTab) -> Element<Message> {
+    button(label)
+        .style(if current == tab {
+            button::primary
+        } else {
+            button::secondary
+        })
+        .on_press(EditorMessage::SetTab(tab).into())
+        .into()
+}
+
+fn layout_button<'a>(
+    label: &'a str,
+    levels: &LevelTableState,
+    layout: LevelLayout,
+) -> Element<'a, Message> {
+    button(label)
+        .style(if levels.layout == layout {
+            button::primary
+        } else {
+            button::secondary
+        })
+        .on_press(LevelTableMessage::SetLayout(layout).into())
+        .into()
+}
+
+/// Collects the rows of a field list, skipping those hidden by the filter.
+/// Every field is a top level field of the save, named as in the file.
+struct Fields<'a> {
+    filter: &'a EditorFilter,
+    rows: Vec<Element<'a, Message>>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(filter: &'a EditorFilter) -> Self {
+        Self {
+            filter,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Whether the row is shown. The search matches the row's name or the
+    /// field's name in the save.
+    fn shows(&self, name: &str, field: &str, changed: bool) -> bool {
+        self.filter.shows_field(name, changed) || self.filter.shows_field(field, changed)
+    }
+
+    fn str(mut self, name: &'a str, value: &str, original: &str, field: &'static str) -> Self {
+        if self.shows(name, field, value != original) {
+            self.rows.push(str_field(
+                name.into(),
+                value,
+                original,
+                FieldAddress::new(Entry::Save, field),
+            ));
+        }
+        self
+    }
+
+    fn num(self, name: &'a str, value: u64, original: u64, field: &'static str) -> Self {
+        self.num_in(name, value, original, COUNT, field)
+    }
+
+    fn num_in(
+        mut self,
+        name: &'a str,
+        value: u64,
+        original: u64,
+        bounds: RangeInclusive<u64>,
+        field: &'static str,
+    ) -> Self {
+        if self.shows(name, field, value != original) {
+            self.rows.push(bounded_num_field(
+                name.into(),
+                value,
+                original,
+                bounds,
+                FieldAddress::new(Entry::Save, field),
+            ));
+        }
+        self
+    }
+
+    fn pick(
+        mut self,
+        name: &'a str,
+        value: &str,
+        original: &str,
+        kind: PickerKind,
+        field: &'static str,
+    ) -> Self {
+        if self.shows(name, field, value != original) {
+            self.rows.push(pick_field(
+                name.into(),
+                value.to_string(),
+                original.to_string(),
+                kind,
+                FieldAddress::new(Entry::Save, field),
+            ));
+        }
+        self
+    }
+
+    fn pick_num(
+        mut self,
+        name: &'a str,
+        value: u64,
+        original: u64,
+        kind: PickerKind,
+        field: &'static str,
+    ) -> Self {
+        if self.shows(name, field, value != original) {
+            self.rows.push(pick_num_field(
+                name.into(),
+                value,
+                original,
+                kind,
+                FieldAddress::new(Entry::Save, field),
+            ));
+        }
+        self
+    }
+
+    fn bool(mut self, name: &'a str, value: bool, original: bool, field: &'static str) -> Self {
+        if self.shows(name, field, value != original) {
+            self.rows.push(bool_field(
+                name.into(),
+                value,
+                original,
+                FieldAddress::new(Entry::Save, field),
+            ));
+        }
+        self
+    }
+
+    fn finish(self) -> Vec<Element<'a, Message>> {
+        self.rows
+    }
+}
+
+/// Button setting `field` back to its original value.
+fn revert_button<'a>(field: FieldAddress, original: FieldValue) -> Element<'a, Message> {
+    button(text("Revert").size(12.0))
+        .style(button::secondary)
+        .padding([2.0, 6.0])
+        .on_press(EditorMessage::SetField(field, original).into())
+        .into()
+}
+
+pub fn str_field<'a>(
+    name: Cow<'a, str>,
+    value: impl Into<String>,
+    placeholder: impl Into<String>,
+    field: FieldAddress,
+) -> Element<'a, Message> {
+    let current = value.into();
+    let original = placeholder.into();
+    let revert = (current != original)
+        .then(|| revert_button(field.clone(), FieldValue::Text(original.clone())));
+
+    row![
+        text(name.clone()),
+        text_input(&original, &current).on_input(move |x| EditorMessage::SetField(
+            field.clone(),
+            FieldValue::Text(x)
+        )
+        .into())
+    ]
+    .push_maybe(revert)
+    .align_y(Vertical::Center)
+    .spacing(8.0)
+    .into()
+}
+
+/// Values a counter in the save can hold, as the game keeps them in 32-bit
+/// signed integers.
+const COUNT: RangeInclusive<u64> = 0..=i32::MAX as u64;
+
+/// Frames per second the game can be limited to, 0 being no limit.
+const FRAMERATE_LIMIT: RangeInclusive<u64> = 0..=1000;
+
+/// Numeric field holding a [`COUNT`].
+pub fn num_field<'a>(
+    name: Cow<'a, str>,
+    value: u64,
+    original: u64,
+    field: FieldAddress,
+) -> Element<'a, Message> {
+    bounded_num_field(name, value, original, COUNT, field)
+}
+
+/// Numeric field whose editor only accepts values within `bounds`.
+pub fn bounded_num_field<'a>(
+    name: Cow<'a, str>,
+    value: u64,
+    original: u64,
+    bounds: RangeInclusive<u64>,
+    field: FieldAddress,
+) -> Element<'a, Message> {
+    let revert =
+        (value != original).then(|| revert_button(field.clone(), FieldValue::Number(original)));
+
+    row![
+        text(name.clone()),
+        button(text(value.to_string()))
+            .style(button::secondary)
+            .on_press(Message::OpenNumericEditor(NumericFieldEditorInit {
+                name: name.to_string(),
+                value,
+                original,
+                bounds,
+                field,
+            })),
+        text(original.to_string()).color(color!(0x999999))
+    ]
+    .push_maybe(revert)
+    .align_y(Vertical::Center)
+    .spacing(8.0)
+    .into()
+}
+
+/// Field whose value is chosen from the options of `kind` in the picker.
+pub fn pick_field<'a>(
+    name: Cow<'a, str>,
+    value: String,
+    original: String,
+    kind: PickerKind,
+    field: FieldAddress,
+) -> Element<'a, Message> {
+    let original_value = if kind.is_numeric() {
+        original.parse().map(FieldValue::Number).ok()
+    } else {
+        Some(FieldValue::Text(original.clone()))
+    };
+    let revert = original_value
+        .filter(|_| value != original)
+        .map(|original| revert_button(field.clone(), original));
+
+    row![
+        text(name.clone()),
+        button(
+            row![
+                text(kind.name(&value)),
+                text(value.clone()).size(12.0).color(color!(0x999999))
+            ]
+            .align_y(Vertical::Center)
+            .spacing(4.0)
+        )
+        .style(button::secondary)
+        .on_press(Message::OpenPicker(PickerInit {
+            name: name.to_string(),
+            value,
+            kind,
+            field,
+        })),
+        text(kind.name(&original)).color(color!(0x999999))
+    ]
+    .push_maybe(revert)
+    .align_y(Vertical::Center)
+    .spacing(8.0)
+    .into()
+}
+
+/// [`pick_field`] for numeric fields.
+pub fn pick_num_field<'a>(
+    name: Cow<'a, str>,
+    value: u64,
+    original: u64,
+    kind: PickerKind,
+    field: FieldAddress,
+) -> Element<'a, Message> {
+    pick_field(name, value.to_string(), original.to_string(), kind, field)
+}
+
+pub fn bool_field<'a>(
+    name: Cow<'a, str>,
+    value: bool,
+    original: bool,
+    field: FieldAddress,
+) -> Element<'a, Message> {
+    let revert =
+        (value != original).then(|| revert_button(field.clone(), FieldValue::Bool(original)));
+
+    row![
+        text(name),
+        checkbox("", value).on_toggle(move |x| EditorMessage::SetField(
+            field.clone(),
+            FieldValue::Bool(x)
+        )
+        .into()),
+        checkbox("", original),
+    ]
+    .push_maybe(revert)
+    .align_y(Vertical::Center)
+    .spacing(8.0)
+    .into()
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum LevelEditMessage {
+    AddDifficulty { remix: bool, difficulty: u64 },
+    RemoveDifficulty(DifficultySlot),
+}
+
+/// Position of a `DifficultyData` inside a level, either in its normal or its
+/// remix list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DifficultySlot {
+    pub remix: bool,
+    pub index: usize,
+}
+
+impl DifficultySlot {
+    pub fn entry(self, level: usize) -> Entry {
+        Entry::Difficulty {
+            level,
+            remix: self.remix,
+            index: self.index,
+        }
+    }
+}
+
+/// Editor for a whole level. With `focused` set only that difficulty entry is
+/// shown instead of every one.
+pub fn level_edit<'a>(
+    index: usize,
+    level: &'a LevelData,
+    original: Option<&'a LevelData>,
+    focused: Option<DifficultySlot>,
+) -> Element<'a, Message> {
+    // Levels added in this session have nothing to compare against.
+    let original = original.unwrap_or(level);
+    let level_field = |field| FieldAddress::new(Entry::Level(index), field);
+
+    let difficulties = |remix: bool| {
+        let (data, original) = if remix {
+            (
+                &level.remix_difficulty_data,
+                &original.remix_difficulty_data,
+            )
+        } else {
+            (&level.difficulty_data, &original.difficulty_data)
+        };
+
+        column(
+            data.iter()
+                .enumerate()
+                .map(move |(id, difficulty)| (DifficultySlot { remix, index: id }, difficulty))
+                .filter(|(slot, _)| focused.is_none_or(|focused| focused == *slot))
+                .map(|(slot, difficulty)| {
+                    high_score_data_edit(
+                        index,
+                        slot,
+                        difficulty,
+                        diff::match_difficulty(original, difficulty),
+                    )
+                }),
+        )
+        .spacing(8.0)
+    };
+
+    let high_scores: Element<'a, Message> = match focused {
+        Some(slot) => column![
+            row![
+                text(focused_title(level, slot)).width(Length::Fill),
+                button("Show all difficulties")
+                    .style(button::secondary)
+                    .on_press(LevelTableMessage::Select(index).into())
+            ]
+            .align_y(Vertical::Center),
+            difficulties(slot.remix)
+        ]
+        .spacing(8.0)
+        .into(),
+        None => column![
+            text("High Score Data"),
+            difficulties(false),
+            add_difficulty(index, level, false),
+            text("Remix High Score Data"),
+            difficulties(true),
+            add_difficulty(index, level, true),
+        ]
+        .spacing(8.0)
+        .into(),
+    };
+
+    container(column![
+        pick_field(
+            "Level Id".into(),
+            level.level_id.clone(),
+            original.level_id.clone(),
+            PickerKind::LevelId,
+            level_field("LevelId")
+        ),
+        pick_num_field(
+            "Stage Type".into(),
+            level.stage_type,
+            original.stage_type,
+            PickerKind::StageType,
+            level_field("StageType")
+        ),
+        bool_field(
+            "Was Completed In Story Mode".into(),
+            level.was_completed_in_story_mode,
+            original.was_completed_in_story_mode,
+            level_field("WasCompletedInStoryMode")
+        ),
+        bool_field(
+            "Was Attempted In Story Mode".into(),
+            level.was_attempted_in_story_mode,
+            original.was_attempted_in_story_mode,
+            level_field("WasAttemptedInStoryMode")
+        ),
+        bool_field(
+            "Was Skipped In Story Mode".into(),
+            level.was_skipped_in_story_mode,
+            original.was_skipped_in_story_mode,
+            level_field("WasSkippedInStoryMode")
+        ),
+        num_field(
+            "Awarded Diamonds".into(),
+            level.awarded_diamonds,
+            original.awarded_diamonds,
+            level_field("AwardedDiamonds")
+        ),
+        num_field(
+            "Awarded Diamonds Remix".into(),
+            level.awarded_diamonds_remix,
+            original.awarded_diamonds_remix,
+            level_field("AwardedDiamondsRemix")
+        ),
+        high_scores
+    ])
+    .padding(8.0)
+    .style(|_| container::Style {
+        border: iced::Border {
+            color: color!(0x222222),
+            width: 1.0.into(),
+            radius: 8.0.into(),
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
+/// Picker adding an entry for one of the difficulties the level is missing.
+fn add_difficulty<'a>(index: usize, level: &LevelData, remix: bool) -> Element<'a, Message> {
+    let data = if remix {
+        &level.remix_difficulty_data
+    } else {
+        &level.difficulty_data
+    };
+
+    let missing = catalog::DIFFICULTIES
+        .iter()
+        .copied()
+        .filter(|difficulty| !data.iter().any(|x| x.difficulty == *difficulty))
+        .map(NewDifficulty)
+        .collect::<Vec<_>>();
+
+    pick_list(missing, None::<NewDifficulty>, move |x| {
+        EditorMessage::LevelEdit {
+            index,
+            message: LevelEditMessage::AddDifficulty {
+                remix,
+                difficulty: x.0,
+            },
+        }
+        .into()
+    })
+    .placeholder("Add difficulty...")
+    .into()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct NewDifficulty(u64);
+
+impl Display for NewDifficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", catalog::difficulty_name(self.0))
+    }
+}
+
+fn focused_title(level: &LevelData, slot: DifficultySlot) -> String {
+    let data = if slot.remix {
+        &level.remix_difficulty_data
+    } else {
+        &level.difficulty_data
+    };
+
+    let name = data
+        .get(slot.index)
+        .map(|x| catalog::difficulty_name(x.difficulty))
+        .unwrap_or_default();
+
+    if slot.remix {
+        format!("{name} (Remix)")
+    } else {
+        name
+    }
+}
+
+fn high_score_data_edit<'a>(
+    level_index: usize,
+    slot: DifficultySlot,
+    data: &DifficultyData,
+    original: Option<&DifficultyData>,
+) -> Element<'a, Message> {
+    let changed = original != Some(data);
+    let original = original.unwrap_or(data);
+    let difficulty_field = |field| FieldAddress::new(slot.entry(level_index), field);
+
+    container(column![
+        row![
+            text(catalog::difficulty_name(data.difficulty)).width(Length::Fill),
+            button("Revert")
+                .style(button::secondary)
+                .on_press_maybe(changed.then(|| {
+                    EditorMessage::Revert(RevertTarget::Difficulty(level_index, slot)).into()
+                })),
+            button("Remove").style(button::danger).on_press(
+                EditorMessage::LevelEdit {
+                    index: level_index,
+                    message: LevelEditMessage::RemoveDifficulty(slot),
+                }
+                .into()
+            )
+        ]
+        .align_y(Vertical::Center),
+        pick_num_field(
+            "Difficulty".into(),
+            data.difficulty,
+            original.difficulty,
+            PickerKind::Difficulty,
+            difficulty_field("Difficulty")
+        ),
+        num_field(
+            "High Score".into(),
+            data.high_score,
+            original.high_score,
+            difficulty_field("HighScore")
+        ),
+        pick_field(
+            "Letter Grade".into(),
+            data.letter_grade.clone(),
+            original.letter_grade.clone(),
+            PickerKind::LetterGrade,
+            difficulty_field("LetterGrade")
+        ),
+        num_field(
+            "Max Combo Count".into(),
+            data.max_combo_count,
+            original.max_combo_count,
+            difficulty_field("MaxComboCount")
+        ),
+        num_field(
+            "Attempts".into(),
+            data.num_attempts,
+            original.num_attempts,
+            difficulty_field("NumAttempts")
+        ),
+        num_field(
+            "Clears".into(),
+            data.num_clears,
+            original.num_clears,
+            difficulty_field("NumClears")
+        ),
+        num_field(
+            "Retries".into(),
+            data.num_retries,
+            original.num_retries,
+            difficulty_field("NumRetries")
+        ),
+        num_field(
+            "Game Overs".into(),
+            data.num_game_overs,
+            original.num_game_overs,
+            difficulty_field("NumGameOvers")
+        ),
+        bool_field(
+            "Has All Perfects".into(),
+            data.has_all_perfects,
+            original.has_all_perfects,
+            difficulty_field("HasAllPerfects")
+        ),
+        bool_field(
+            "Has Full Combo Rhythm Shift".into(),
+            data.has_full_combo_rhythm_rift,
+            original.has_full_combo_rhythm_rift,
+            difficulty_field("HasFullComboRhythmRift")
+        ),
+    ])
+    .padding(8.0)
+    .width(Length::Fill)
+    .style(|_| container::Style {
+        border: iced::Border {
+            color: color!(0x222222),
+            width: 1.0.into(),
+            radius: 8.0.into(),
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates;
+
+    fn with_levels(ids: &[&str]) -> SaveGame {
+        let mut save = templates::save();
+        for id in ids {
+            save.level_data.push(templates::level(id.to_string()));
+        }
+        save
+    }
+
+    fn diamonds(level: usize, value: u64) -> EditorMessage {
+        EditorMessage::SetField(
+            FieldAddress::new(Entry::Level(level), "AwardedDiamonds"),
+            FieldValue::Number(value),
+        )
+    }
+
+    #[test]
+    fn reverts_replay_as_what_they_changed() {
+        let mut state = EditorState::new(with_levels(&["A"]), PathBuf::new(), Encoding::default());
+        let _ = state.update(diamonds(0, 5));
+        let _ = state.update(EditorMessage::StartRecording);
+        let _ = state.update(EditorMessage::Revert(RevertTarget::Level(0)));
+        let recorded = state.recording.clone().unwrap();
+
+        assert_eq!(state.data.level_data[0].awarded_diamonds, 0);
+        assert!(matches!(
+            recorded.steps[..],
+            [Step {
+                message: EditorMessage::ApplyChanges(_),
+                ..
+            }]
+        ));
+
+        // The other save was loaded with the diamonds already set, so a
+        // revert of its own would leave them.
+        let mut other = with_levels(&["B", "A"]);
+        other.level_data[1].awarded_diamonds = 5;
+        let mut target = EditorState::new(other, PathBuf::new(), Encoding::default());
+        let skipped = target.replay(&recorded);
+
+        assert!(skipped.is_empty());
+        assert_eq!(target.data.level_data[1].awarded_diamonds, 0);
+    }
+
+    #[test]
+    fn reverts_of_missing_entries_are_reported() {
+        let mut state = EditorState::new(with_levels(&["A"]), PathBuf::new(), Encoding::default());
+        let _ = state.update(diamonds(0, 5));
+        let _ = state.update(EditorMessage::StartRecording);
+        let _ = state.update(EditorMessage::Revert(RevertTarget::All));
+        let recorded = state.recording.clone().unwrap();
+
+        let mut target = EditorState::new(with_levels(&["B"]), PathBuf::new(), Encoding::default());
+        let skipped = target.replay(&recorded);
+
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(target.data, with_levels(&["B"]));
+    }
+}
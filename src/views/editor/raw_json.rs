@@ -0,0 +1,125 @@
+use std::fmt::Display;
+
+use iced::{
+    Element, Font, Length,
+    alignment::Vertical,
+    color,
+    widget::{
+        button, column, row, text, text_editor,
+        text_editor::{Action, Content, Motion},
+    },
+};
+
+use crate::{Message, models::SaveGame};
+
+use super::EditorMessage;
+
+/// The save as pretty-printed JSON, edited as plain text.
+#[derive(Default)]
+pub struct JsonState {
+    content: Content,
+    /// The save the text was last generated from or parsed into. The text is
+    /// only regenerated once the editor's data no longer matches it, so
+    /// formatting and invalid edits aren't thrown away while typing.
+    synced: Option<SaveGame>,
+    error: Option<JsonError>,
+}
+
+#[derive(Clone, Debug)]
+pub struct JsonError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(err: serde_json::Error) -> Self {
+        Self {
+            line: err.line(),
+            column: err.column(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl JsonState {
+    /// Regenerates the text if `save` was changed by something other than the
+    /// text itself.
+    pub fn sync(&mut self, save: &SaveGame) {
+        if self.synced.as_ref() == Some(save) {
+            return;
+        }
+
+        self.content = Content::with_text(&serde_json::to_string_pretty(save).unwrap_or_default());
+        self.synced = Some(save.clone());
+        self.error = None;
+    }
+
+    /// Performs `action` on the text. Returns the save it parses into after
+    /// an edit, if it's valid.
+    pub fn perform(&mut self, action: Action) -> Option<SaveGame> {
+        let is_edit = action.is_edit();
+        self.content.perform(action);
+
+        if !is_edit {
+            return None;
+        }
+
+        match serde_json::from_str::<SaveGame>(&self.content.text()) {
+            Ok(save) => {
+                self.error = None;
+                self.synced = Some(save.clone());
+                Some(save)
+            }
+            Err(err) => {
+                self.error = Some(err.into());
+                None
+            }
+        }
+    }
+
+    /// Moves the cursor to where the text stopped being valid.
+    pub fn go_to_error(&mut self) {
+        let Some(error) = &self.error else {
+            return;
+        };
+
+        self.content.perform(Action::Move(Motion::DocumentStart));
+        for _ in 1..error.line {
+            self.content.perform(Action::Move(Motion::Down));
+        }
+        for _ in 1..error.column {
+            self.content.perform(Action::Move(Motion::Right));
+        }
+    }
+}
+
+pub fn view(state: &JsonState) -> Element<Message> {
+    column![
+        text_editor(&state.content)
+            .font(Font::MONOSPACE)
+            .height(Length::Fill)
+            .on_action(|x| EditorMessage::EditJson(x).into())
+    ]
+    .push_maybe(state.error.as_ref().map(|error| {
+        row![
+            text(error.to_string())
+                .color(color!(0xFF0000))
+                .width(Length::Fill),
+            button("Go to error")
+                .style(button::secondary)
+                .on_press(EditorMessage::GoToJsonError.into())
+        ]
+        .align_y(Vertical::Center)
+        .spacing(4.0)
+    }))
+    .spacing(4.0)
+    .height(Length::Fill)
+    .into()
+}
@@ -0,0 +1,149 @@
+use iced::{
+    Element, Length, color,
+    widget::{button, column, container, row, text},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Message,
+    catalog::PickerKind,
+    field::{Entry, FieldAddress},
+    models::{StoryBeatData, StorylineData},
+    templates,
+};
+
+use super::{EditorMessage, bool_field, num_field, pick_field};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum StorylineEditMessage {
+    AddStoryBeat,
+    RemoveStoryBeat(usize),
+}
+
+pub fn apply(storyline: &mut StorylineData, message: StorylineEditMessage) {
+    match message {
+        StorylineEditMessage::AddStoryBeat => storyline
+            .story_beat_data
+            .push(templates::story_beat(String::new())),
+        StorylineEditMessage::RemoveStoryBeat(index) => {
+            if index < storyline.story_beat_data.len() {
+                storyline.story_beat_data.remove(index);
+            }
+        }
+    }
+}
+
+fn message(index: usize, message: StorylineEditMessage) -> EditorMessage {
+    EditorMessage::StorylineEdit { index, message }
+}
+
+pub fn storyline_edit<'a>(
+    index: usize,
+    storyline: &'a StorylineData,
+    original: Option<&'a StorylineData>,
+) -> Element<'a, Message> {
+    let original = original.unwrap_or(storyline);
+
+    container(
+        column![
+            row![
+                text(format!("Storyline {}", storyline.storyline_characters)).width(Length::Fill),
+                button("Remove")
+                    .style(button::danger)
+                    .on_press(EditorMessage::RemoveStoryline(index).into())
+            ],
+            num_field(
+                "Storyline Characters".into(),
+                storyline.storyline_characters,
+                original.storyline_characters,
+                FieldAddress::new(Entry::Storyline(index), "storylineCharacters")
+            ),
+            bool_field(
+                "Has Unlocked Storyline".into(),
+                storyline.has_unlocked_storyline,
+                original.has_unlocked_storyline,
+                FieldAddress::new(Entry::Storyline(index), "HasUnlockedStoryline")
+            ),
+            bool_field(
+                "Has Completed Storyline".into(),
+                storyline.has_completed_storyline,
+                original.has_completed_storyline,
+                FieldAddress::new(Entry::Storyline(index), "HasCompletedStoryline")
+            ),
+            text("Story Beats"),
+            column(
+                storyline
+                    .story_beat_data
+                    .iter()
+                    .enumerate()
+                    .map(|(beat_index, beat)| story_beat_edit(
+                        index,
+                        beat_index,
+                        beat,
+                        original
+                            .story_beat_data
+                            .iter()
+                            .find(|x| x.level_id == beat.level_id)
+                            .unwrap_or(beat)
+                    ))
+            )
+            .spacing(4.0),
+            button("Add Story Beat")
+                .style(button::secondary)
+                .on_press(message(index, StorylineEditMessage::AddStoryBeat).into())
+        ]
+        .spacing(4.0),
+    )
+    .padding(8.0)
+    .style(|_| container::Style {
+        border: iced::Border {
+            color: color!(0x222222),
+            width: 1.0.into(),
+            radius: 8.0.into(),
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
+fn story_beat_edit<'a>(
+    storyline_index: usize,
+    index: usize,
+    beat: &'a StoryBeatData,
+    original: &'a StoryBeatData,
+) -> Element<'a, Message> {
+    let beat_field = |field| {
+        FieldAddress::new(
+            Entry::StoryBeat {
+                storyline: storyline_index,
+                index,
+            },
+            field,
+        )
+    };
+
+    row![
+        pick_field(
+            "Level Id".into(),
+            beat.level_id.clone(),
+            original.level_id.clone(),
+            PickerKind::LevelId,
+            beat_field("LevelId")
+        ),
+        num_field(
+            "Times Played".into(),
+            beat.times_played,
+            original.times_played,
+            beat_field("TimesPlayed")
+        ),
+        button("Remove").style(button::danger).on_press(
+            message(
+                storyline_index,
+                StorylineEditMessage::RemoveStoryBeat(index)
+            )
+            .into()
+        )
+    ]
+    .spacing(8.0)
+    .into()
+}
@@ -0,0 +1,430 @@
+//! Fallback for saves that don't parse as `SaveGame`, such as saves written
+//! by a newer version of the game. The save is edited as a plain JSON tree.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use iced::{
+    Element, Length, Task,
+    alignment::Vertical,
+    color,
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_input},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+use crate::{
+    Message,
+    diff::{self, Change, Path, Segment},
+    modals::dialog::DialogState,
+    models::SaveGame,
+    save_file::{self, Encoding},
+    schema::{self, Issue},
+    versions::{self, Version},
+};
+
+pub struct GenericState {
+    value: Value,
+    original: Value,
+    path: PathBuf,
+    encoding: Encoding,
+    /// Why the save couldn't be opened in the regular editor.
+    parse_error: String,
+    issues: Vec<Issue>,
+    /// Objects and lists that are shown opened, by path.
+    expanded: HashSet<String>,
+    /// Text typed into number fields that isn't a number yet, by path.
+    drafts: HashMap<String, String>,
+    error: Option<String>,
+    changes: Vec<Change>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum GenericMessage {
+    Toggle(Path),
+    SetValue(Path, Value),
+    EditNumber(Path, String),
+    Save,
+    /// Opens the save in the regular editor once it parses.
+    OpenEditor,
+    /// Upgrades or downgrades the save to another known version.
+    Migrate(Version),
+}
+
+impl GenericState {
+    pub fn new(value: Value, parse_error: String, path: PathBuf, encoding: Encoding) -> Self {
+        let issues = schema::check(&value);
+
+        // Everything leading up to a field that failed is opened, so the
+        // fields are visible right away.
+        let expanded = issues
+            .iter()
+            .flat_map(|issue| {
+                (1..issue.path.0.len()).map(|length| Path(issue.path.0[..length].to_vec()))
+            })
+            .map(|path| path.to_string())
+            .collect();
+
+        Self {
+            original: value.clone(),
+            value,
+            path,
+            encoding,
+            parse_error,
+            issues,
+            expanded,
+            drafts: HashMap::new(),
+            error: None,
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn changes(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| "SaveGame".into())
+    }
+
+    /// The save as `SaveGame`, if it parses as one by now.
+    fn typed(&self) -> Option<SaveGame> {
+        serde_json::from_value(self.value.clone()).ok()
+    }
+
+    fn set(&mut self, path: &Path, new: Value) {
+        if let Some(value) = get_mut(&mut self.value, path) {
+            *value = new;
+        }
+
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        self.issues = schema::check(&self.value);
+        self.changes = diff::diff(&self.original, &self.value);
+    }
+
+    pub fn update(&mut self, message: GenericMessage) -> Task<Message> {
+        match message {
+            GenericMessage::Toggle(path) => {
+                let key = path.to_string();
+                if !self.expanded.remove(&key) {
+                    self.expanded.insert(key);
+                }
+            }
+            GenericMessage::SetValue(path, value) => self.set(&path, value),
+            GenericMessage::EditNumber(path, input) => {
+                match serde_json::from_str::<Number>(input.trim()) {
+                    Ok(number) => {
+                        self.drafts.remove(&path.to_string());
+                        self.set(&path, Value::Number(number));
+                    }
+                    Err(_) => {
+                        self.drafts.insert(path.to_string(), input);
+                    }
+                }
+            }
+            GenericMessage::Save => {
+                match save_file::write(&self.path, &self.value, self.encoding) {
+                    Ok(()) => {
+                        self.original = self.value.clone();
+                        self.changes.clear();
+                        self.error = None;
+                    }
+                    Err(err) => {
+                        let error = format!("Failed to save {}: {}", self.path.display(), err);
+                        self.error = Some(error.clone());
+                        return Task::done(Message::OpenDialog(DialogState::alert(
+                            "Couldn't save",
+                            error,
+                        )));
+                    }
+                }
+            }
+            GenericMessage::Migrate(target) => match versions::migrate(&mut self.value, target) {
+                Ok(_) => {
+                    self.error = None;
+                    self.refresh();
+                }
+                Err(err) => self.error = Some(err.to_string()),
+            },
+            GenericMessage::OpenEditor => {
+                if let Some(save) = self.typed().filter(|_| !self.is_dirty()) {
+                    return Task::done(Message::Loaded(save, self.path.clone(), self.encoding));
+                }
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// The value at `path`, which only uses field and index segments.
+fn get_mut<'a>(value: &'a mut Value, path: &Path) -> Option<&'a mut Value> {
+    path.0
+        .iter()
+        .try_fold(value, |value, segment| match segment {
+            Segment::Field(name) => value.get_mut(name),
+            Segment::Index(index) => value.get_mut(*index),
+            Segment::Key { .. } => None,
+        })
+}
+
+pub fn view(state: &GenericState) -> Element<Message> {
+    let fields = match &state.value {
+        Value::Object(fields) => column(
+            fields
+                .iter()
+                .map(|(name, value)| node(state, Path::default(), name, value)),
+        ),
+        value => column![node(state, Path::default(), "", value)],
+    };
+
+    let issues = state.issues.iter().map(|issue| {
+        text(issue.to_string())
+            .size(14.0)
+            .color(color!(0xFF0000))
+            .into()
+    });
+
+    container(
+        row![
+            column![
+                text("This save doesn't match the format the editor knows").size(22.0),
+                text(state.parse_error.clone()).color(color!(0x999999)),
+                version_notice(&state.value),
+                scrollable(fields.spacing(4.0).padding(2.0)).height(Length::Fill)
+            ]
+            .spacing(8.0)
+            .width(Length::FillPortion(3))
+            .height(Length::Fill),
+            column![
+                text("Actions").size(22.0),
+                button(text(match state.changes.len() {
+                    0 => "Save".to_string(),
+                    1 => "Save (1 change)".to_string(),
+                    changes => format!("Save ({changes} changes)"),
+                }))
+                .style(button::success)
+                .width(Length::Fill)
+                .on_press(GenericMessage::Save.into()),
+                button("Open in Editor")
+                    .style(button::secondary)
+                    .width(Length::Fill)
+                    .on_press_maybe(
+                        (state.issues.is_empty() && !state.is_dirty())
+                            .then(|| GenericMessage::OpenEditor.into())
+                    ),
+                row![
+                    text("Saving in"),
+                    text(state.path.display().to_string()).color(color!(1, 1, 1, 0.8))
+                ]
+                .spacing(2.0),
+                text(format!("as {}", state.encoding))
+                    .size(12.0)
+                    .color(color!(0x999999)),
+                text(match state.issues.len() {
+                    0 => "Every field parses, save to open it in the editor".to_string(),
+                    1 => "1 field doesn't parse".to_string(),
+                    issues => format!("{issues} fields don't parse"),
+                }),
+                scrollable(column(issues).spacing(4.0)).height(Length::Fill),
+            ]
+            .push_maybe(
+                state
+                    .error
+                    .clone()
+                    .map(|error| text(error).color(color!(0xFF0000))),
+            )
+            .spacing(4.0)
+            .height(Length::Fill)
+            .width(Length::Shrink)
+            .max_width(350.0),
+        ]
+        .height(Length::Fill)
+        .spacing(4.0),
+    )
+    .padding(12.0)
+    .into()
+}
+
+/// The save's version, and the versions it can be migrated to.
+fn version_notice(value: &Value) -> Element<Message> {
+    let Some(version) = versions::version_of(value) else {
+        return text("The save has no version")
+            .color(color!(0xCC8800))
+            .into();
+    };
+
+    let targets = version.targets();
+
+    row![
+        text(if version.is_known() {
+            format!("Version {version}")
+        } else {
+            format!("Version {version} isn't one the editor knows")
+        })
+        .color_maybe((!version.is_known()).then_some(color!(0xCC8800)))
+        .width(Length::Fill)
+    ]
+    .push_maybe((!targets.is_empty()).then(|| {
+        pick_list(targets, None::<Version>, |x| {
+            GenericMessage::Migrate(x).into()
+        })
+        .placeholder("Migrate to version...")
+    }))
+    .align_y(Vertical::Center)
+    .spacing(4.0)
+    .into()
+}
+
+/// A field or list entry. Objects and lists can be opened to show what's
+/// inside them, everything else is edited in place.
+fn node<'a>(
+    state: &'a GenericState,
+    parent: Path,
+    name: &str,
+    value: &'a Value,
+) -> Element<'a, Message> {
+    let path = parent.push(Segment::Field(name.to_string()));
+    node_at(state, path, name.to_string(), value)
+}
+
+fn node_at<'a>(
+    state: &'a GenericState,
+    path: Path,
+    label: String,
+    value: &'a Value,
+) -> Element<'a, Message> {
+    let key = path.to_string();
+    let failed = state.issues.iter().any(|issue| issue.path == path);
+    let label = text(label)
+        .width(Length::Fixed(280.0))
+        .color_maybe(failed.then_some(color!(0xFF0000)));
+
+    match value {
+        Value::Object(_) | Value::Array(_) => {
+            let expanded = state.expanded.contains(&key);
+            let summary = match value {
+                Value::Object(fields) => format!("{} fields", fields.len()),
+                Value::Array(entries) => format!("{} entries", entries.len()),
+                _ => String::new(),
+            };
+
+            let header = button(
+                row![
+                    text(if expanded { "v" } else { ">" }).width(Length::Fixed(16.0)),
+                    label,
+                    text(summary).color(color!(0x999999))
+                ]
+                .align_y(Vertical::Center),
+            )
+            .style(button::text)
+            .padding(0.0)
+            .on_press(GenericMessage::Toggle(path.clone()).into());
+
+            column![header]
+                .push_maybe(expanded.then(|| {
+                    column(children(state, &path, value))
+                        .spacing(4.0)
+                        .padding([0.0, 16.0])
+                }))
+                .spacing(4.0)
+                .into()
+        }
+        Value::Bool(checked) => row![
+            label,
+            checkbox("", *checked).on_toggle(move |x| GenericMessage::SetValue(
+                path.clone(),
+                Value::Bool(x)
+            )
+            .into())
+        ]
+        .align_y(Vertical::Center)
+        .spacing(8.0)
+        .into(),
+        Value::Number(number) => {
+            let draft = state.drafts.get(&key);
+            let input = draft.cloned().unwrap_or_else(|| number.to_string());
+
+            row![
+                label,
+                text_input("", &input).on_input(move |x| GenericMessage::EditNumber(
+                    path.clone(),
+                    x
+                )
+                .into())
+            ]
+            .push_maybe(draft.map(|_| text("Not a number").color(color!(0xFF0000))))
+            .align_y(Vertical::Center)
+            .spacing(8.0)
+            .into()
+        }
+        Value::String(current) => row![
+            label,
+            text_input("", current).on_input(move |x| GenericMessage::SetValue(
+                path.clone(),
+                Value::String(x)
+            )
+            .into())
+        ]
+        .align_y(Vertical::Center)
+        .spacing(8.0)
+        .into(),
+        Value::Null => row![label, text("null").color(color!(0x999999))]
+            .spacing(8.0)
+            .into(),
+    }
+}
+
+fn children<'a>(
+    state: &'a GenericState,
+    path: &Path,
+    value: &'a Value,
+) -> Vec<Element<'a, Message>> {
+    match value {
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(name, value)| node(state, path.clone(), name, value))
+            .collect(),
+        Value::Array(entries) => {
+            // Entries of known lists are labelled with their key, like the
+            // level ID of a level.
+            let list_key = match path.0.last() {
+                Some(Segment::Field(field)) => diff::list_key(field),
+                _ => None,
+            };
+
+            entries
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    let label = match list_key.and_then(|key| value.get(key)) {
+                        Some(Value::String(key)) => format!("[{index}] {key}"),
+                        Some(key) => format!("[{index}] {key}"),
+                        None => format!("[{index}]"),
+                    };
+
+                    node_at(state, path.push(Segment::Index(index)), label, value)
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+impl Into<Message> for GenericMessage {
+    fn into(self) -> Message {
+        Message::Generic(self)
+    }
+}
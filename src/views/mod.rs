@@ -1,12 +1,17 @@
 use editor::EditorState;
+use generic::GenericState;
 use pick_file::PickFileState;
 
 pub mod editor;
+pub mod generic;
 pub mod pick_file;
+pub mod tab_bar;
 
 pub enum View {
     PickFile(PickFileState),
     Editor(EditorState),
+    /// A save that doesn't parse as `SaveGame`.
+    Generic(GenericState),
 }
 
 impl Default for View {
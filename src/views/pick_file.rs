@@ -1,39 +1,69 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
 
 use iced::{
-    Background, Border, Element, Length, Task, color,
-    widget::{button, column, container, row, text},
+    Background, Border, Element, Font, Length, Task, color,
+    widget::{button, checkbox, column, container, row, scrollable, text},
 };
 use rfd::AsyncFileDialog;
+use serde::{Deserialize, Serialize};
 
-use crate::{Message, models::SaveGame};
+use crate::{
+    Message,
+    config::Config,
+    diagnostic::Diagnostic,
+    locator::{self, FoundSave},
+    save_file::{self, SaveFileError},
+};
 
 #[derive(Default)]
 pub struct PickFileState {
     path: String,
     valid: bool,
-    error: Option<String>,
+    error: Option<SaveFileError>,
+    /// Saves found in the game's save folders.
+    found: Vec<FoundSave>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum PickFileMessage {
     OpenDialog,
     UserChangedPath(String),
     Submit,
+    /// Looks for saves in the folders the game keeps them in.
+    Discover,
+    Discovered(Vec<FoundSave>),
+    OpenPath(PathBuf),
 }
 
 impl PickFileState {
     pub fn update(&mut self, message: PickFileMessage) -> Task<Message> {
         match message {
-            PickFileMessage::OpenDialog => Task::future(async {
-                let file = AsyncFileDialog::new().pick_file().await;
-                let Some(file) = file else {
-                    return Message::None;
-                };
+            PickFileMessage::OpenDialog => {
+                let folder = self
+                    .found
+                    .first()
+                    .and_then(|save| save.path.parent())
+                    .map(|folder| folder.to_path_buf());
 
-                let path = file.path().to_string_lossy().to_string();
-                PickFileMessage::UserChangedPath(path).into()
-            }),
+                Task::future(async {
+                    let mut dialog = AsyncFileDialog::new();
+                    if let Some(folder) = folder {
+                        dialog = dialog.set_directory(folder);
+                    }
+
+                    let file = dialog.pick_file().await;
+                    let Some(file) = file else {
+                        return Message::None;
+                    };
+
+                    let path = file.path().to_string_lossy().to_string();
+                    PickFileMessage::UserChangedPath(path).into()
+                })
+            }
             PickFileMessage::UserChangedPath(path) => {
                 self.path = path;
 
@@ -47,29 +77,63 @@ impl PickFileState {
             PickFileMessage::Submit => {
                 let Ok(path) = PathBuf::from_str(&self.path);
 
-                let content = match std::fs::read_to_string(&path) {
-                    Ok(x) => x,
+                match save_file::load(&path) {
+                    Ok((data, encoding)) => Task::done(Message::Loaded(data, path, encoding)),
+                    // Valid JSON that doesn't match `SaveGame` can still be
+                    // edited as a plain tree.
+                    Err(SaveFileError::Parse(diagnostic)) => match save_file::load_value(&path) {
+                        Ok((value, encoding)) => Task::done(Message::LoadedGeneric(
+                            value,
+                            diagnostic.summary(),
+                            path,
+                            encoding,
+                        )),
+                        Err(err) => {
+                            self.error = Some(err);
+                            Task::none()
+                        }
+                    },
                     Err(err) => {
-                        self.error = Some(err.to_string());
-                        return Task::none();
+                        self.error = Some(err);
+                        Task::none()
                     }
-                };
+                }
+            }
+            PickFileMessage::Discover => Task::future(async {
+                let saves = tokio::task::spawn_blocking(locator::find_saves)
+                    .await
+                    .unwrap_or_default();
+                PickFileMessage::Discovered(saves).into()
+            }),
+            PickFileMessage::Discovered(saves) => {
+                self.found = saves;
 
-                let data: SaveGame = match serde_json::from_str(&content) {
-                    Ok(x) => x,
-                    Err(err) => {
-                        self.error = Some(format!("Error parsing json: {}", err.to_string()));
-                        return Task::none();
+                // The most recent save is the likeliest pick, unless a path
+                // was already given.
+                match self.found.first() {
+                    Some(save) if self.path.is_empty() => {
+                        let path = save.path.to_string_lossy().to_string();
+                        self.update(PickFileMessage::UserChangedPath(path))
                     }
-                };
-
-                Task::done(Message::Loaded(data, path))
+                    _ => Task::none(),
+                }
+            }
+            PickFileMessage::OpenPath(path) => {
+                let path = path.to_string_lossy().to_string();
+                self.update(PickFileMessage::UserChangedPath(path))
+                    .chain(self.update(PickFileMessage::Submit))
             }
         }
     }
 }
 
-pub fn view(state: &PickFileState) -> Element<Message> {
+pub fn view<'a>(state: &'a PickFileState, config: &'a Config) -> Element<'a, Message> {
+    let recent = config
+        .recent
+        .iter()
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+
     container(
         container(
             column![
@@ -89,11 +153,17 @@ pub fn view(state: &PickFileState) -> Element<Message> {
                     None
                 })
             ]
-            .push_maybe(if let Some(error) = state.error.clone() {
-                text(error).color(color!(0xFF0000)).into()
-            } else {
-                None
-            })
+            .push_maybe(state.error.as_ref().map(|error| match error {
+                SaveFileError::Parse(diagnostic) => diagnostic_view(diagnostic),
+                error => text(error.to_string()).color(color!(0xFF0000)).into(),
+            }))
+            .push_maybe((!recent.is_empty()).then(|| recent_view(&recent)))
+            .push_maybe((!state.found.is_empty()).then(|| found_view(&state.found)))
+            .push(
+                checkbox("Reopen the last saves on start", config.reopen_last)
+                    .on_toggle(Message::SetReopenLast)
+                    .text_size(14.0),
+            )
             .spacing(8.0),
         )
         .style(|_| container::Style {
@@ -122,6 +192,138 @@ pub fn view(state: &PickFileState) -> Element<Message> {
     .into()
 }
 
+/// The parse error with the part of the file it's in, the line with the
+/// error in red and a caret under its column.
+fn diagnostic_view(diagnostic: &Diagnostic) -> Element<Message> {
+    let lines = diagnostic.excerpt.iter().map(|line| {
+        let number = text(format!("{:>5} | ", line.number))
+            .font(Font::MONOSPACE)
+            .color(color!(0x999999));
+
+        match line.caret {
+            Some(caret) => column![
+                row![
+                    number,
+                    text(line.text.clone())
+                        .font(Font::MONOSPACE)
+                        .color(color!(0xFF0000))
+                ],
+                text(format!("{:>5} | {}^", "", " ".repeat(caret)))
+                    .font(Font::MONOSPACE)
+                    .color(color!(0xFF0000))
+            ]
+            .into(),
+            None => row![number, text(line.text.clone()).font(Font::MONOSPACE)].into(),
+        }
+    });
+
+    column![
+        text(format!("Error parsing json: {}", diagnostic.summary())).color(color!(0xFF0000)),
+        text(format!(
+            "Line {}, column {}",
+            diagnostic.line, diagnostic.column
+        ))
+        .size(12.0)
+        .color(color!(0x999999)),
+        scrollable(column(lines)).direction(scrollable::Direction::Horizontal(Default::default()))
+    ]
+    .spacing(4.0)
+    .into()
+}
+
+/// Saves opened before, the latest first.
+fn recent_view<'a>(paths: &[&PathBuf]) -> Element<'a, Message> {
+    let paths = paths.iter().map(|path| {
+        let file_name = path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let folder = path
+            .parent()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        button(column![
+            text(file_name),
+            text(folder).size(12.0).color(color!(0x999999))
+        ])
+        .style(button::secondary)
+        .width(Length::Fill)
+        .on_press(PickFileMessage::OpenPath(path.to_path_buf()).into())
+        .into()
+    });
+
+    column![text("Recent"), column(paths).spacing(4.0)]
+        .spacing(4.0)
+        .into()
+}
+
+/// The saves found on this computer with what's in them, each opened with
+/// one click.
+fn found_view(saves: &[FoundSave]) -> Element<Message> {
+    let saves = saves.iter().map(|save| {
+        let file_name = save
+            .path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let modified = match save.modified {
+            Some(modified) => format!("{}, changed {}", save.location, ago(modified)),
+            None => save.location.clone(),
+        };
+
+        let content = match &save.summary {
+            Ok(summary) => {
+                let name = if summary.save_name.is_empty() {
+                    file_name
+                } else {
+                    format!("{} ({file_name})", summary.save_name)
+                };
+
+                column![
+                    text(name),
+                    text(format!(
+                        "{:.0}% complete, {} diamonds, booted {} times",
+                        summary.completion, summary.diamonds, summary.times_booted
+                    ))
+                    .size(12.0),
+                    text(modified).size(12.0).color(color!(0x999999))
+                ]
+            }
+            Err(error) => column![
+                text(file_name),
+                text(error.clone()).size(12.0).color(color!(0xFF0000)),
+                text(modified).size(12.0).color(color!(0x999999))
+            ],
+        };
+
+        button(content)
+            .style(button::secondary)
+            .width(Length::Fill)
+            .on_press(PickFileMessage::OpenPath(save.path.clone()).into())
+            .into()
+    });
+
+    column![
+        text("Saves found on this computer"),
+        scrollable(column(saves).spacing(4.0)).height(Length::Shrink)
+    ]
+    .spacing(4.0)
+    .into()
+}
+
+/// How long ago `time` was, roughly.
+pub fn ago(time: SystemTime) -> String {
+    let elapsed = time.elapsed().unwrap_or(Duration::ZERO).as_secs();
+
+    match elapsed {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} min ago", elapsed / 60),
+        3600..86400 => format!("{} h ago", elapsed / 3600),
+        _ => format!("{} days ago", elapsed / 86400),
+    }
+}
+
 impl Into<Message> for PickFileMessage {
     fn into(self) -> Message {
         Message::PickFile(self)
@@ -0,0 +1,47 @@
+use iced::{
+    Element,
+    widget::{button, row, scrollable, text},
+};
+
+use crate::{Message, PendingAction, TabId};
+
+/// A button for each open tab with one to close it next to it, then one to
+/// open another tab.
+pub fn view<'a>(
+    tabs: impl Iterator<Item = (TabId, String)>,
+    active: TabId,
+) -> Element<'a, Message> {
+    let tabs = tabs.map(|(id, title)| {
+        let close = Message::Tab(
+            id,
+            Box::new(Message::RequestAction(PendingAction::CloseTab)),
+        );
+
+        row![
+            button(text(title).size(14.0))
+                .style(if id == active {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .on_press(Message::SelectTab(id)),
+            button(text("x").size(14.0))
+                .style(button::text)
+                .on_press(close)
+        ]
+        .into()
+    });
+
+    scrollable(
+        row(tabs)
+            .push(
+                button(text("+").size(14.0))
+                    .style(button::secondary)
+                    .on_press(Message::NewTab),
+            )
+            .spacing(4.0)
+            .padding(4.0),
+    )
+    .direction(scrollable::Direction::Horizontal(Default::default()))
+    .into()
+}